@@ -924,7 +924,8 @@ impl bindgen::callbacks::ParseCallbacks for ParseCallback {
 
 	#[cfg(feature = "serde")]
 	fn add_derives(&self, info: &bindgen::callbacks::DeriveInfo<'_>) -> Vec<String> {
-		if info.name.starts_with("Slang") && info.kind == bindgen::callbacks::TypeKind::Enum {
+		let is_public_enum = info.name.starts_with("Slang") || info.name == "slang_CompilerOptionName";
+		if is_public_enum && info.kind == bindgen::callbacks::TypeKind::Enum {
 			return vec!["serde::Serialize".into(), "serde::Deserialize".into()];
 		}
 		vec![]
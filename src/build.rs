@@ -0,0 +1,367 @@
+//! Compiling *Slang* shaders from a `build.rs` script.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     slang::build::ShaderBuilder::new("shaders")
+//!         .target(slang::CompileTarget::Spirv, Some("spirv_1_5"))
+//!         .build()
+//!         .unwrap();
+//! }
+//!
+//! // lib.rs
+//! mod shaders {
+//!     include!(concat!(env!("OUT_DIR"), "/shaders.rs"));
+//! }
+//! // A module per shader file with a constant per entry point and target, e.g. `shaders::lighting::MAIN_SPIRV`
+//! ```
+//!
+//! [`core_module`] precompiles *Slang*'s core module the same way, so the binary doesn't have to compile it at startup.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::pipeline::{link_and_generate, session_config};
+use crate::{CompileTarget, CompilerOptions, Diagnostics, GlobalSession};
+
+#[derive(Debug)]
+pub enum BuildError {
+	Io(PathBuf, std::io::Error),
+	/// `OUT_DIR` is not set and no output directory was given.
+	MissingOutDir,
+	/// Compiling a shader file failed.
+	Slang(PathBuf, crate::Error),
+	/// Two artifacts got the same constant or file name, e.g. for `foo-bar.slang` and `foo_bar.slang`.
+	Conflict(String, PathBuf, PathBuf),
+}
+
+impl std::fmt::Display for BuildError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BuildError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+			BuildError::MissingOutDir => write!(f, "OUT_DIR is not set, is this running from a build script?"),
+			BuildError::Slang(path, error) => write!(f, "failed to compile {}:\n{error}", path.display()),
+			BuildError::Conflict(name, first, second) => {
+				write!(f, "{} and {} both produce {name}", first.display(), second.display())
+			}
+		}
+	}
+}
+
+impl std::error::Error for BuildError {}
+
+pub type Result<T> = std::result::Result<T, BuildError>;
+
+/// Compiles *Slang*'s core module and saves it as `slang-core-module.bin` in `OUT_DIR`, to be embedded with
+/// `include_bytes!` and loaded by [`GlobalSession::with_core_module`].
+pub fn core_module() -> Result<PathBuf> {
+	let out_dir = std::env::var_os("OUT_DIR").map(PathBuf::from).ok_or(BuildError::MissingOutDir)?;
+	let path = out_dir.join("slang-core-module.bin");
+	let slang_error = |error| BuildError::Slang(path.clone(), error);
+
+	let global_session = GlobalSession::new_without_core_module()
+		.ok_or_else(|| slang_error(crate::Error::Code(crate::SLANG_FAIL)))?;
+	global_session.compile_core_module().map_err(slang_error)?;
+	let archive = global_session.save_core_module(crate::ArchiveType::RiffLz4).map_err(slang_error)?;
+	crate::core_module::write_archive(&path, archive.as_slice()).map_err(|e| BuildError::Io(path.clone(), e))?;
+
+	Ok(path)
+}
+
+struct Target {
+	format: CompileTarget,
+	profile: Option<String>,
+}
+
+/// A compiled entry point written by [`ShaderBuilder::build`].
+#[derive(Debug, Clone)]
+pub struct Artifact {
+	/// The shader file relative to the source directory.
+	pub source: PathBuf,
+	pub entry_point: String,
+	pub target: CompileTarget,
+	pub path: PathBuf,
+	/// The name of the constant in the generated Rust module, e.g. `lighting::MAIN_SPIRV`.
+	pub constant: String,
+}
+
+/// Compiles every `.slang` file in a directory tree for one or more targets.
+pub struct ShaderBuilder {
+	source_dir: PathBuf,
+	out_dir: Option<PathBuf>,
+	targets: Vec<Target>,
+	search_paths: Vec<PathBuf>,
+	options: CompilerOptions,
+	rust_module: String,
+}
+
+impl ShaderBuilder {
+	pub fn new(source_dir: impl Into<PathBuf>) -> Self {
+		Self {
+			source_dir: source_dir.into(),
+			out_dir: None,
+			targets: Vec::new(),
+			search_paths: Vec::new(),
+			options: CompilerOptions::default(),
+			rust_module: "shaders.rs".to_owned(),
+		}
+	}
+
+	/// Adds a target to compile every entry point for. Defaults to SPIR-V when no target is added.
+	pub fn target(mut self, format: CompileTarget, profile: Option<&str>) -> Self {
+		self.targets.push(Target { format, profile: profile.map(str::to_owned) });
+		self
+	}
+
+	/// Adds a directory to search for imported modules in addition to the source directory.
+	pub fn search_path(mut self, path: impl Into<PathBuf>) -> Self {
+		self.search_paths.push(path.into());
+		self
+	}
+
+	pub fn options(mut self, options: CompilerOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// The directory to write artifacts to, `OUT_DIR` by default.
+	pub fn out_dir(mut self, out_dir: impl Into<PathBuf>) -> Self {
+		self.out_dir = Some(out_dir.into());
+		self
+	}
+
+	/// The file name of the generated Rust module, `shaders.rs` by default.
+	pub fn rust_module(mut self, file_name: &str) -> Self {
+		self.rust_module = file_name.to_owned();
+		self
+	}
+
+	/// Compiles all shaders, writes the artifacts and the Rust module and tells cargo which files to watch.
+	pub fn build(self) -> Result<Vec<Artifact>> {
+		let out_dir = match &self.out_dir {
+			Some(out_dir) => out_dir.clone(),
+			None => std::env::var_os("OUT_DIR").map(PathBuf::from).ok_or(BuildError::MissingOutDir)?,
+		};
+		let artifact_dir = out_dir.join("shaders");
+		std::fs::create_dir_all(&artifact_dir).map_err(|e| BuildError::Io(artifact_dir.clone(), e))?;
+
+		// Watching the directory itself picks up added and removed files
+		println!("cargo:rerun-if-changed={}", self.source_dir.display());
+
+		let mut sources = Vec::new();
+		collect_sources(&self.source_dir, Path::new(""), &mut sources)?;
+		sources.sort();
+
+		let global_session = GlobalSession::new().ok_or(BuildError::Slang(
+			self.source_dir.clone(),
+			crate::Error::Code(crate::SLANG_FAIL),
+		))?;
+
+		let mut artifacts = Vec::new();
+		for source in &sources {
+			artifacts.extend(self.compile_file(&global_session, source, &artifact_dir)?);
+		}
+		check_conflicts(&artifacts)?;
+
+		let rust_module_path = out_dir.join(&self.rust_module);
+		std::fs::write(&rust_module_path, rust_module(&artifacts))
+			.map_err(|e| BuildError::Io(rust_module_path, e))?;
+
+		Ok(artifacts)
+	}
+
+	fn compile_file(&self, global_session: &GlobalSession, source: &Path, artifact_dir: &Path) -> Result<Vec<Artifact>> {
+		let slang_error = |error| BuildError::Slang(source.to_owned(), error);
+
+		let default_target = [Target { format: CompileTarget::Spirv, profile: None }];
+		let targets = if self.targets.is_empty() { &default_target[..] } else { &self.targets };
+
+		// A fresh session per file keeps modules from being shared across unrelated shaders
+		let config = session_config(
+			targets.iter().map(|target| (target.format, target.profile.as_deref())),
+			std::iter::once(&self.source_dir).chain(&self.search_paths),
+			&self.options,
+		);
+		let session = config.create_session(global_session).map_err(slang_error)?;
+
+		let module_path = source.to_string_lossy().replace('\\', "/");
+		let (module, diagnostics) = session.load_module_with_diagnostics(&module_path).map_err(slang_error)?;
+		emit_warnings(&diagnostics);
+		for dependency in module.dependency_file_paths() {
+			println!("cargo:rerun-if-changed={dependency}");
+		}
+
+		let stem = source.with_extension("").to_string_lossy().replace('\\', "/");
+		let modules = stem.split('/').map(rust_identifier).collect::<Vec<_>>();
+		// Escaped components keep `a/b.slang` and `a.b.slang` apart
+		let file_stem = modules.iter().map(|module| module.trim_start_matches("r#")).collect::<Vec<_>>().join(".");
+		let module_name = modules.join("::");
+		let target_names = target_names(targets);
+		let mut artifacts = Vec::new();
+
+		for entry_point in module.entry_points() {
+			let entry_point_name = entry_point.function_reflection().name().unwrap_or("main").to_owned();
+			let program = session
+				.create_composite_component_type(&[module.clone().into(), entry_point.into()])
+				.map_err(slang_error)?;
+			let mut diagnostics = Diagnostics::default();
+			let generated = link_and_generate(&program, 1, targets.len(), &mut diagnostics).map_err(slang_error)?;
+			emit_warnings(&diagnostics);
+
+			for ((target_index, target), code) in targets.iter().enumerate().zip(&generated.code) {
+				let (target_constant, target_file) = &target_names[target_index];
+				let path = artifact_dir.join(format!("{file_stem}.{entry_point_name}{target_file}"));
+				std::fs::write(&path, &code[0]).map_err(|e| BuildError::Io(path.clone(), e))?;
+
+				let constant = format!("{}_{target_constant}", constant_identifier(&entry_point_name));
+
+				artifacts.push(Artifact {
+					source: source.to_owned(),
+					entry_point: entry_point_name.clone(),
+					target: target.format,
+					path,
+					constant: format!("{module_name}::{constant}"),
+				});
+			}
+		}
+
+		Ok(artifacts)
+	}
+}
+
+/// The constant suffix and the file extension of every target, e.g. `SPIRV` and `.spv`. Targets sharing a format are
+/// told apart by their profile, e.g. `SPIRV_GLSL_450` and `.spirv_glsl_450.spv`, and so are formats sharing an
+/// extension.
+fn target_names(targets: &[Target]) -> Vec<(String, String)> {
+	targets.iter().map(|target| {
+		let profile = target.profile.as_deref().unwrap_or("default");
+		let shares_format = targets.iter().filter(|other| other.format == target.format).count() > 1;
+		let shares_extension = targets.iter().filter(|other| extension(other.format) == extension(target.format)).count() > 1;
+
+		let mut constant = constant_identifier(&format!("{:?}", target.format));
+		let mut file = String::new();
+		if shares_format {
+			constant = format!("{constant}_{}", constant_identifier(profile));
+		}
+		if shares_extension {
+			write!(file, ".{}", constant.to_ascii_lowercase()).unwrap();
+		}
+		write!(file, ".{}", extension(target.format)).unwrap();
+		(constant, file)
+	}).collect()
+}
+
+/// Fails if two artifacts would be embedded under the same constant or were written to the same file.
+fn check_conflicts(artifacts: &[Artifact]) -> Result<()> {
+	let mut constants = std::collections::HashMap::new();
+	let mut paths = std::collections::HashMap::new();
+
+	for artifact in artifacts {
+		let conflict = match constants.insert(artifact.constant.as_str(), &artifact.source) {
+			Some(other) => Some((artifact.constant.clone(), other)),
+			None => paths.insert(&artifact.path, &artifact.source)
+				.map(|other| (artifact.path.display().to_string(), other)),
+		};
+		if let Some((name, other)) = conflict {
+			return Err(BuildError::Conflict(name, other.clone(), artifact.source.clone()));
+		}
+	}
+
+	Ok(())
+}
+
+fn collect_sources(root: &Path, relative: &Path, sources: &mut Vec<PathBuf>) -> Result<()> {
+	let dir = root.join(relative);
+	let entries = std::fs::read_dir(&dir).map_err(|e| BuildError::Io(dir.clone(), e))?;
+
+	for entry in entries {
+		let entry = entry.map_err(|e| BuildError::Io(dir.clone(), e))?;
+		let path = relative.join(entry.file_name());
+		if entry.path().is_dir() {
+			collect_sources(root, &path, sources)?;
+		} else if path.extension().is_some_and(|e| e == "slang") {
+			sources.push(path);
+		}
+	}
+
+	Ok(())
+}
+
+fn emit_warnings(diagnostics: &crate::Diagnostics) {
+	for warning in diagnostics.warnings() {
+		println!("cargo:warning={warning}");
+	}
+}
+
+fn extension(target: CompileTarget) -> &'static str {
+	match target {
+		CompileTarget::Spirv => "spv",
+		CompileTarget::SpirvAsm => "spvasm",
+		CompileTarget::Dxil => "dxil",
+		CompileTarget::Dxbc => "dxbc",
+		CompileTarget::Glsl => "glsl",
+		CompileTarget::Hlsl => "hlsl",
+		CompileTarget::Wgsl => "wgsl",
+		CompileTarget::Metal => "metal",
+		CompileTarget::MetalLib => "metallib",
+		CompileTarget::CudaSource => "cu",
+		CompileTarget::Ptx => "ptx",
+		CompileTarget::CppSource | CompileTarget::HostCppSource => "cpp",
+		CompileTarget::CSource => "c",
+		_ => "bin",
+	}
+}
+
+/// The name of a constant, which can't clash with a keyword as it is upper case.
+fn constant_identifier(name: &str) -> String {
+	let identifier = name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' });
+	let identifier = identifier.collect::<String>();
+	match identifier.starts_with(|c: char| c.is_ascii_digit()) {
+		true => format!("_{identifier}"),
+		false => identifier,
+	}
+}
+
+/// The name of a module, escaping keywords, e.g. `r#type` for `type.slang`.
+fn rust_identifier(name: &str) -> String {
+	let identifier = name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect::<String>();
+	match identifier.as_str() {
+		// These can't be raw identifiers
+		"self" | "Self" | "super" | "crate" | "_" => format!("{identifier}_"),
+		s if crate::codegen::KEYWORDS.contains(&s) => format!("r#{identifier}"),
+		s if s.starts_with(|c: char| c.is_ascii_digit()) => format!("_{identifier}"),
+		_ => identifier,
+	}
+}
+
+/// Nests the constants of all artifacts in modules following the source directory layout.
+fn rust_module(artifacts: &[Artifact]) -> String {
+	#[derive(Default)]
+	struct Node {
+		children: std::collections::BTreeMap<String, Node>,
+		constants: Vec<(String, PathBuf)>,
+	}
+
+	fn write_node(out: &mut String, node: &Node, indent: usize) {
+		let tabs = "\t".repeat(indent);
+		for (name, path) in &node.constants {
+			writeln!(out, "{tabs}pub const {name}: &[u8] = include_bytes!({:?});", path.to_string_lossy()).unwrap();
+		}
+		for (name, child) in &node.children {
+			writeln!(out, "{tabs}pub mod {name} {{").unwrap();
+			write_node(out, child, indent + 1);
+			writeln!(out, "{tabs}}}").unwrap();
+		}
+	}
+
+	let mut root = Node::default();
+	for artifact in artifacts {
+		let (modules, constant) = artifact.constant.rsplit_once("::").unwrap();
+		let node = modules.split("::").fold(&mut root, |node, module| node.children.entry(module.to_owned()).or_default());
+		node.constants.push((constant.to_owned(), artifact.path.clone()));
+	}
+
+	let mut out = String::from("// Generated by shader_slang::build, do not edit.\n\n");
+	write_node(&mut out, &root, 0);
+	out
+}
@@ -0,0 +1,83 @@
+//! Sources added to the core module, so that every shader can use their declarations without an `import`.
+//!
+//! ```ignore
+//! let builtins = slang::BuiltinBundle::new()
+//!     .file("shaders/engine/bindless.slang")?
+//!     .file("shaders/engine/debug.slang")?;
+//! let global_session = slang::GlobalSession::with_builtins(&builtins)?;
+//! ```
+
+use std::ffi::CString;
+use std::path::Path;
+
+use crate::{Diagnostics, Error, GlobalSession, Interface, Result, SLANG_E_INVALID_ARG, SLANG_FAIL, SessionDesc, vcall};
+
+impl GlobalSession {
+	/// A global session with the core module and all sources of `builtins`, which every session created from it sees.
+	///
+	/// The only sessions created before a source is added are the temporary ones [`add_builtins`](Self::add_builtins)
+	/// checks it in. Each of them is dropped right away and sees the sources added before, so later sources can use
+	/// earlier ones.
+	pub fn with_builtins(builtins: &BuiltinBundle) -> Result<GlobalSession> {
+		let global_session = GlobalSession::new().ok_or(Error::Code(SLANG_FAIL))?;
+		builtins.add_to(&global_session)?;
+		Ok(global_session)
+	}
+
+	/// Adds `source` to the core module and returns the warnings reported for it. `path` is only used in diagnostics.
+	/// Only sessions created afterwards see the new declarations.
+	///
+	/// *Slang* aborts on errors in builtin code, so the source is first checked as a module of a temporary session and
+	/// only added if that succeeds.
+	pub fn add_builtins(&self, path: &str, source: &str) -> Result<Diagnostics> {
+		let c_path = CString::new(path).map_err(|_| Error::Code(SLANG_E_INVALID_ARG))?;
+		let c_source = CString::new(source).map_err(|_| Error::Code(SLANG_E_INVALID_ARG))?;
+
+		let session = self.create_session(&SessionDesc::default()).ok_or(Error::Code(SLANG_FAIL))?;
+		let module_name = Path::new(path).file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+		let (_, diagnostics) = session.load_module_from_source_string_with_diagnostics(&module_name, path, source)?;
+
+		vcall!(self, addBuiltins(c_path.as_ptr(), c_source.as_ptr()));
+		Ok(diagnostics)
+	}
+}
+
+/// Builtin sources to add to a global session together, in order, so later sources can use earlier ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BuiltinBundle {
+	sources: Vec<(String, String)>,
+}
+
+impl BuiltinBundle {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn source(mut self, path: &str, source: &str) -> Self {
+		self.sources.push((path.to_owned(), source.to_owned()));
+		self
+	}
+
+	/// Reads a source file into the bundle.
+	pub fn file(self, path: impl AsRef<Path>) -> std::io::Result<Self> {
+		let path = path.as_ref();
+		let source = std::fs::read_to_string(path)?;
+		Ok(self.source(&path.to_string_lossy(), &source))
+	}
+
+	/// The paths and sources in the order they are added.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, &str)> {
+		self.sources.iter().map(|(path, source)| (path.as_str(), source.as_str()))
+	}
+
+	/// Adds all sources to `global_session`, see [`GlobalSession::add_builtins`]. Stops at the first source that
+	/// fails, leaving the earlier ones added.
+	pub fn add_to(&self, global_session: &GlobalSession) -> Result<Diagnostics> {
+		let mut diagnostics = Diagnostics::default();
+		for (path, source) in self.iter() {
+			diagnostics.extend(global_session.add_builtins(path, source)?);
+		}
+		Ok(diagnostics)
+	}
+}
@@ -0,0 +1,350 @@
+//! Generation of Rust struct definitions that match reflected uniform and buffer layouts.
+//!
+//! ```ignore
+//! let params = reflection.find_parameter_by_name("params").unwrap(); // a `ConstantBuffer<Params>`
+//! let mut structs = slang::codegen::StructWriter::new();
+//! structs.add(params.type_layout().unwrap());
+//! std::fs::write(out_dir.join("params.rs"), structs.finish())?;
+//! ```
+//!
+//! Every generated struct is `#[repr(C)]` with explicit padding fields, so it contains no implicit padding and can
+//! derive `bytemuck::Pod`. The expected size and the offset of every field are checked by compile-time assertions.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::reflection::TypeLayout;
+use crate::{MatrixLayoutMode, ParameterCategory, ScalarType, TypeKind};
+
+const DEFAULT_DERIVES: [&str; 4] = ["Clone", "Copy", "bytemuck::Pod", "bytemuck::Zeroable"];
+
+/// Strict and reserved keywords, which can only be used as raw identifiers.
+pub(crate) const KEYWORDS: [&str; 50] = [
+	"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+	"if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct",
+	"super", "trait", "true", "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final",
+	"gen", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Collects Rust struct definitions for reflected type layouts, including all structs they contain.
+pub struct StructWriter {
+	derives: Vec<String>,
+	/// The Rust name of every struct emitted so far, by *Slang* name and [`layout_signature`].
+	emitted: HashMap<(String, String), String>,
+	/// All names defined so far.
+	names: HashSet<String>,
+	anonymous_count: usize,
+	out: String,
+}
+
+impl Default for StructWriter {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl StructWriter {
+	pub fn new() -> Self {
+		Self {
+			derives: DEFAULT_DERIVES.iter().map(|d| d.to_string()).collect(),
+			emitted: HashMap::new(),
+			names: HashSet::new(),
+			anonymous_count: 0,
+			out: String::new(),
+		}
+	}
+
+	/// Replaces the derives added to every generated struct, `Clone, Copy, bytemuck::Pod, bytemuck::Zeroable` by
+	/// default.
+	pub fn derives(mut self, derives: &[&str]) -> Self {
+		self.derives = derives.iter().map(|d| d.to_string()).collect();
+		self
+	}
+
+	/// Adds the Rust definition for a type layout and returns the Rust type to use for it.
+	///
+	/// A struct is defined once per layout. If it is added again with a different layout, e.g. as the element of a
+	/// `ConstantBuffer` with std140 rules and of a `StructuredBuffer` with std430 rules, the second definition is named
+	/// with a `Layout2` suffix, the third with `Layout3` and so on.
+	///
+	/// Constant buffers, parameter blocks and structured buffers are unwrapped to their element type, so the layout
+	/// of a reflected buffer parameter can be passed in directly.
+	pub fn add(&mut self, type_layout: &TypeLayout) -> String {
+		let type_layout = unwrap_buffer(type_layout);
+		match self.field_type(type_layout) {
+			Some((ty, _)) => ty,
+			None => format!("[u8; {}]", uniform_size(type_layout)),
+		}
+	}
+
+	/// Like [`add`](Self::add), but names the generated struct instead of using the *Slang* type name.
+	pub fn add_named(&mut self, name: &str, type_layout: &TypeLayout) -> String {
+		self.add_struct(name.to_owned(), unwrap_buffer(type_layout))
+	}
+
+	/// The generated definitions.
+	pub fn finish(self) -> String {
+		self.out
+	}
+
+	/// The Rust type and its size for a layout, or `None` if the layout can only be represented as raw bytes.
+	fn field_type(&mut self, type_layout: &TypeLayout) -> Option<(String, usize)> {
+		let size = uniform_size(type_layout);
+
+		match type_layout.kind() {
+			TypeKind::Scalar => scalar(type_layout.scalar_type()?).map(|(ty, size)| (ty.to_owned(), size)),
+			TypeKind::Vector => {
+				let (ty, scalar_size) = scalar(type_layout.scalar_type()?)?;
+				let count = type_layout.element_count()?;
+				Some((format!("[{ty}; {count}]"), count * scalar_size))
+			}
+			TypeKind::Matrix => {
+				let (ty, scalar_size) = scalar(type_layout.scalar_type()?)?;
+				let (rows, columns) = (type_layout.row_count()? as usize, type_layout.column_count()? as usize);
+				let (major, minor) = match type_layout.matrix_layout_mode() {
+					MatrixLayoutMode::ColumnMajor => (columns, rows),
+					_ => (rows, columns),
+				};
+
+				// Rows or columns are padded to a common stride in some layouts, e.g. to 16 bytes in std140
+				let stride = size / major;
+				(size.is_multiple_of(major) && stride.is_multiple_of(scalar_size) && stride >= minor * scalar_size)
+					.then(|| (format!("[[{ty}; {}]; {major}]", stride / scalar_size), size))
+			}
+			TypeKind::Array => {
+				let count = type_layout.element_count().filter(|&count| count > 0)?;
+				let element = type_layout.element_type_layout()?;
+				let (element_ty, element_size) = self.field_type(element)?;
+				let stride = type_layout.element_stride(ParameterCategory::Uniform);
+
+				if stride * count != size {
+					// The last element isn't padded to the stride, which can't be expressed as a Rust array
+					return None;
+				}
+				let element_ty = match stride.checked_sub(element_size)? {
+					0 => element_ty,
+					padding => self.padded_element(element, element_ty, element_size, padding)?,
+				};
+				Some((format!("[{element_ty}; {count}]"), size))
+			}
+			TypeKind::Struct => {
+				let name = match type_layout.name() {
+					Some(name) => sanitize_type_name(name),
+					None => {
+						self.anonymous_count += 1;
+						format!("Anonymous{}", self.anonymous_count)
+					}
+				};
+				Some((self.add_struct(name, type_layout), size))
+			}
+			_ => None,
+		}
+	}
+
+	/// Widens an array element to the array stride.
+	fn padded_element(
+		&mut self,
+		element: &TypeLayout,
+		element_ty: String,
+		element_size: usize,
+		padding: usize,
+	) -> Option<String> {
+		match element.kind() {
+			// Scalars and vectors are widened by additional components
+			TypeKind::Scalar | TypeKind::Vector => {
+				let (ty, scalar_size) = scalar(element.scalar_type()?)?;
+				padding.is_multiple_of(scalar_size).then(|| format!("[{ty}; {}]", (element_size + padding) / scalar_size))
+			}
+			_ => {
+				let name = format!("{}Stride{}", sanitize_type_name(&element_ty), element_size + padding);
+				if self.names.insert(name.clone()) {
+					let mut definition = self.struct_header(&name);
+					writeln!(definition, "\tpub value: {element_ty},").unwrap();
+					writeln!(definition, "\tpub _pad: [u8; {padding}],").unwrap();
+					writeln!(definition, "}}").unwrap();
+					writeln!(definition, "const _: () = assert!(std::mem::size_of::<{name}>() == {});", element_size + padding).unwrap();
+					self.out.push_str(&definition);
+					self.out.push('\n');
+				}
+				Some(name)
+			}
+		}
+	}
+
+	fn add_struct(&mut self, slang_name: String, type_layout: &TypeLayout) -> String {
+		let key = (slang_name, layout_signature(type_layout));
+		if let Some(name) = self.emitted.get(&key) {
+			return name.clone();
+		}
+
+		let name = (1..)
+			.map(|index| match index {
+				1 => key.0.clone(),
+				_ => format!("{}Layout{index}", key.0),
+			})
+			.find(|name| !self.names.contains(name))
+			.unwrap();
+		self.names.insert(name.clone());
+		self.emitted.insert(key, name.clone());
+
+		let field_names = type_layout.fields()
+			.map(|field| sanitize_field_name(field.name().unwrap_or("field")))
+			.collect::<HashSet<_>>();
+
+		let mut fields = String::new();
+		let mut assertions = String::new();
+		let mut cursor = 0;
+		let mut pad_count = 0;
+		let mut pad = |fields: &mut String, bytes: usize| {
+			// Shader fields may be called `_pad0` as well
+			let pad_name = loop {
+				let pad_name = format!("_pad{pad_count}");
+				pad_count += 1;
+				if !field_names.contains(&pad_name) {
+					break pad_name;
+				}
+			};
+			writeln!(fields, "\tpub {pad_name}: [u8; {bytes}],").unwrap();
+		};
+
+		for field in type_layout.fields() {
+			let Some(field_layout) = field.type_layout() else { continue };
+			let size = uniform_size(field_layout);
+			if size == 0 {
+				// Resources and other opaque types are bound separately and take no space in the struct
+				continue;
+			}
+
+			let offset = field.offset(ParameterCategory::Uniform);
+			if offset > cursor {
+				pad(&mut fields, offset - cursor);
+			}
+
+			let field_name = sanitize_field_name(field.name().unwrap_or("field"));
+			let field_ty = match self.field_type(field_layout) {
+				Some((ty, _)) => ty,
+				None => {
+					writeln!(fields, "\t/// Raw bytes of `{}`, which has no direct Rust equivalent in this layout", field_layout.name().unwrap_or("?")).unwrap();
+					format!("[u8; {size}]")
+				}
+			};
+			writeln!(fields, "\tpub {field_name}: {field_ty},").unwrap();
+			writeln!(assertions, "const _: () = assert!(std::mem::offset_of!({name}, {field_name}) == {offset});").unwrap();
+			cursor = offset + size;
+		}
+
+		let size = uniform_size(type_layout);
+		if size > cursor {
+			pad(&mut fields, size - cursor);
+		}
+
+		// Nested structs were appended while collecting the fields, so this one follows its dependencies
+		let mut definition = self.struct_header(&name);
+		definition.push_str(&fields);
+		writeln!(definition, "}}").unwrap();
+		writeln!(definition, "const _: () = assert!(std::mem::size_of::<{name}>() == {size});").unwrap();
+		definition.push_str(&assertions);
+		self.out.push_str(&definition);
+		self.out.push('\n');
+
+		name
+	}
+
+	fn struct_header(&self, name: &str) -> String {
+		let mut header = String::from("#[repr(C)]\n");
+		if !self.derives.is_empty() {
+			writeln!(header, "#[derive({})]", self.derives.join(", ")).unwrap();
+		}
+		writeln!(header, "pub struct {name} {{").unwrap();
+		header
+	}
+}
+
+fn unwrap_buffer(type_layout: &TypeLayout) -> &TypeLayout {
+	match type_layout.kind() {
+		TypeKind::ConstantBuffer | TypeKind::ParameterBlock | TypeKind::TextureBuffer | TypeKind::ShaderStorageBuffer
+		| TypeKind::Resource => type_layout.element_type_layout().unwrap_or(type_layout),
+		_ => type_layout,
+	}
+}
+
+/// Everything about a layout that affects the generated definition, so that a struct laid out by different rules gets
+/// a definition per layout.
+fn layout_signature(type_layout: &TypeLayout) -> String {
+	let mut signature = format!(
+		"{}/{}",
+		uniform_size(type_layout),
+		type_layout.alignment(ParameterCategory::Uniform)
+	);
+	match type_layout.kind() {
+		TypeKind::Struct => {
+			signature.push('{');
+			for field in type_layout.fields() {
+				if let Some(field_layout) = field.type_layout() {
+					let offset = field.offset(ParameterCategory::Uniform);
+					write!(signature, "{offset}:{};", layout_signature(field_layout)).unwrap();
+				}
+			}
+			signature.push('}');
+		}
+		TypeKind::Array => {
+			let stride = type_layout.element_stride(ParameterCategory::Uniform);
+			let element = type_layout.element_type_layout().map(layout_signature).unwrap_or_default();
+			write!(signature, "[{stride}x{element}]").unwrap();
+		}
+		TypeKind::Matrix => write!(signature, "{:?}", type_layout.matrix_layout_mode()).unwrap(),
+		_ => {}
+	}
+	signature
+}
+
+fn uniform_size(type_layout: &TypeLayout) -> usize {
+	type_layout.size(ParameterCategory::Uniform)
+}
+
+/// The Rust type and size for a scalar. `bool` is 4 bytes in all GPU layouts and `half` is stored as its raw bits.
+fn scalar(scalar_type: ScalarType) -> Option<(&'static str, usize)> {
+	Some(match scalar_type {
+		ScalarType::Bool => ("u32", 4),
+		ScalarType::Int8 => ("i8", 1),
+		ScalarType::Uint8 => ("u8", 1),
+		ScalarType::Int16 => ("i16", 2),
+		ScalarType::Uint16 => ("u16", 2),
+		ScalarType::Float16 => ("u16", 2),
+		ScalarType::Int32 => ("i32", 4),
+		ScalarType::Uint32 => ("u32", 4),
+		ScalarType::Float32 => ("f32", 4),
+		ScalarType::Int64 => ("i64", 8),
+		ScalarType::Uint64 => ("u64", 8),
+		ScalarType::Float64 => ("f64", 8),
+		_ => return None,
+	})
+}
+
+fn sanitize_type_name(name: &str) -> String {
+	let mut sanitized = String::new();
+	let mut upper = true;
+	for c in name.chars() {
+		if c.is_ascii_alphanumeric() {
+			sanitized.push(if upper { c.to_ascii_uppercase() } else { c });
+			upper = false;
+		} else {
+			upper = true;
+		}
+	}
+	match sanitized.chars().next() {
+		Some(c) if c.is_ascii_digit() => format!("T{sanitized}"),
+		Some(_) => sanitized,
+		None => "Unnamed".to_owned(),
+	}
+}
+
+fn sanitize_field_name(name: &str) -> String {
+	let sanitized = name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect::<String>();
+	match sanitized.as_str() {
+		"self" | "Self" | "super" | "crate" => format!("{sanitized}_"),
+		s if KEYWORDS.contains(&s) => format!("r#{sanitized}"),
+		s if s.starts_with(|c: char| c.is_ascii_digit()) => format!("_{sanitized}"),
+		_ => sanitized,
+	}
+}
@@ -0,0 +1,518 @@
+//////
+//
+// Imports
+//
+
+// Standard library
+use std::{
+	collections::{BTreeSet, HashMap}, ffi::{CStr, CString, c_char, c_void}, mem::ManuallyDrop,
+	sync::atomic::{AtomicU32, Ordering}
+};
+
+// Local imports
+use crate::{*, com_impls::*};
+
+
+
+//////
+//
+// Traits
+//
+
+/// The trait of types that can serve shader sources and modules to a *Slang* session in place of the OS file system.
+/// Wrap an implementation in a [`BoxedFileSystem`] and hand it to [`SessionDesc::file_system`] to use it.
+///
+/// All paths passed to the methods of this trait are normalized by [`normalize_path`] beforehand, i.e. they use `/` as
+/// separator, are relative to the root of the file system and contain no `.` or `..` segments. The root itself is
+/// represented by the empty string.
+pub trait ShaderFileSystem: Send + Sync
+{
+	/// Loads the contents of the file at the given path, returning `None` if no such file exists.
+	fn load_file (&self, path: &str) -> Option<Vec<u8>>;
+
+	/// Reports whether the given path refers to a file or a directory, or `None` if it refers to nothing. The default
+	/// implementation considers every loadable path a file and nothing a directory.
+	fn path_type (&self, path: &str) -> Option<PathType> {
+		self.load_file(path).map(|_| PathType::File)
+	}
+
+	/// Lists the names and types of the immediate children of the directory at the given path. The default
+	/// implementation lists nothing.
+	fn read_dir (&self, _path: &str) -> Vec<(PathType, String)> {
+		Vec::new()
+	}
+}
+
+
+
+//////
+//
+// Structs
+//
+
+/// A [`ShaderFileSystem`] keeping all files in a `HashMap` in memory.
+#[derive(Default,Clone)]
+pub struct MemoryFileSystem {
+	files: HashMap<String, Vec<u8>>,
+}
+impl MemoryFileSystem
+{
+	/// Creates an empty in-memory file system.
+	pub fn new () -> Self {
+		Self::default()
+	}
+
+	/// Adds a file to the file system, replacing any previous file at the same path.
+	pub fn insert (&mut self, path: &str, contents: impl Into<Vec<u8>>) {
+		self.files.insert(normalize_path(path), contents.into());
+	}
+
+	/// Builder-style variant of [`insert`](Self::insert).
+	pub fn with_file (mut self, path: &str, contents: impl Into<Vec<u8>>) -> Self {
+		self.insert(path, contents);
+		self
+	}
+
+	/// Removes the file at the given path, returning its contents if it existed.
+	pub fn remove (&mut self, path: &str) -> Option<Vec<u8>> {
+		self.files.remove(&normalize_path(path))
+	}
+}
+impl ShaderFileSystem for MemoryFileSystem
+{
+	fn load_file (&self, path: &str) -> Option<Vec<u8>> {
+		self.files.get(path).cloned()
+	}
+
+	fn path_type (&self, path: &str) -> Option<PathType> {
+		path_type_from_keys(self.files.keys().map(String::as_str), path)
+	}
+
+	fn read_dir (&self, path: &str) -> Vec<(PathType, String)> {
+		dir_entries_from_keys(self.files.keys().map(String::as_str), path)
+	}
+}
+impl<P: AsRef<str>, C: Into<Vec<u8>>> FromIterator<(P, C)> for MemoryFileSystem {
+	fn from_iter<I: IntoIterator<Item=(P, C)>> (iter: I) -> Self {
+		let mut fs = Self::new();
+		for (path, contents) in iter {
+			fs.insert(path.as_ref(), contents);
+		}
+		fs
+	}
+}
+
+/// A [`ShaderFileSystem`] serving files that are baked into the binary, typically created using the [`embed_files!`]
+/// macro.
+#[derive(Clone,Copy)]
+pub struct StaticFileSystem {
+	files: &'static [(&'static str, &'static [u8])],
+}
+impl StaticFileSystem
+{
+	/// Creates a file system from a static table of `(path, contents)` pairs. Paths in the table must already be in
+	/// the normalized form described for [`ShaderFileSystem`].
+	pub const fn new (files: &'static [(&'static str, &'static [u8])]) -> Self {
+		Self { files }
+	}
+}
+impl ShaderFileSystem for StaticFileSystem
+{
+	fn load_file (&self, path: &str) -> Option<Vec<u8>> {
+		self.files.iter().find(|(p, _)| *p == path).map(|(_, contents)| contents.to_vec())
+	}
+
+	fn path_type (&self, path: &str) -> Option<PathType> {
+		path_type_from_keys(self.files.iter().map(|(p, _)| *p), path)
+	}
+
+	fn read_dir (&self, path: &str) -> Vec<(PathType, String)> {
+		dir_entries_from_keys(self.files.iter().map(|(p, _)| *p), path)
+	}
+}
+
+/// A *Rust* implementation of [`ISlangFileSystemExt`](sys::ISlangFileSystemExt) that forwards all file accesses to a
+/// boxed [`ShaderFileSystem`].
+#[repr(C)]
+pub struct BoxedFileSystem {
+	/// The VTable binding the COM interface to our struct.
+	vtable_: *const sys::IFileSystemExtVtable,
+
+	/// We implement reference counting using *Rust* atomics.
+	ref_count: AtomicU32,
+
+	/// The actual file system.
+	inner: Box<dyn ShaderFileSystem>,
+}
+impl BoxedFileSystem
+{
+	/// Wraps the given file system into a new COM object with a reference count of 1.
+	pub fn new (file_system: impl ShaderFileSystem + 'static) -> *mut BoxedFileSystem {
+		Self::from_box(Box::new(file_system))
+	}
+
+	/// Like [`new`](Self::new), but for file systems that are already boxed.
+	pub fn from_box (file_system: Box<dyn ShaderFileSystem>) -> *mut BoxedFileSystem {
+		// Allocate our object and return it casted to ISlangFileSystemExt pointer type
+		let mut boxed = Box::new(BoxedFileSystem {
+			vtable_: &VTABLE,
+			ref_count: AtomicU32::new(1),
+			inner: file_system,
+		});
+		let ptr: *mut BoxedFileSystem = &mut *boxed;
+		// We must not drop the Box; transfer ownership to COM. Use ManuallyDrop.
+		let _ = ManuallyDrop::new(boxed);
+		ptr
+	}
+
+	/// Reference to the wrapped file system.
+	pub fn inner (&self) -> &dyn ShaderFileSystem {
+		self.inner.as_ref()
+	}
+
+	#[inline]
+	fn this<'a>(this: *mut sys::ISlangUnknown) -> &'a BoxedFileSystem {
+		// Safety: our object layout is compatible; the incoming pointer is one we created.
+		unsafe { &*(this as *mut BoxedFileSystem) }
+	}
+
+	#[inline]
+	fn this_void<'a>(this: *mut c_void) -> &'a BoxedFileSystem {
+		unsafe { &*(this as *mut BoxedFileSystem) }
+	}
+}
+unsafe impl Object for BoxedFileSystem {
+	type Vtable = sys::IFileSystemExtVtable;
+	const IID: UUID = uuid(0x5fb632d2_979d_4481_9fee663c3f1449e1);
+
+	#[inline(always)]
+	unsafe fn as_raw<T>(&self) -> *mut T {
+		self as *const Self as *mut T
+	}
+
+	#[inline(always)]
+	unsafe fn add_ref (&self) -> u32 {
+		unsafe { add_ref(self.as_raw()) }
+	}
+
+	#[inline(always)]
+	unsafe fn release(&self) -> u32 {
+		unsafe { release(self.as_raw()) }
+	}
+}
+
+/// The `IID` of `ISlangCastable`.
+const ICASTABLE_IID: UUID = uuid(0x87ede0e1_4852_44b0_8bf2cb31874de239);
+
+/// The `IID` of `ISlangFileSystem`.
+const IFILESYSTEM_IID: UUID = uuid(0x003a09fc_3a4d_4ba0_ad601fd863a915ab);
+
+
+
+//////
+//
+// Functions
+//
+
+/// Brings a path into the canonical form used by [`ShaderFileSystem`] implementations: `\` separators are turned into
+/// `/`, empty and `.` segments are dropped, `..` segments are resolved where possible and leading slashes are removed.
+pub fn normalize_path (path: &str) -> String
+{
+	let mut segments: Vec<&str> = Vec::new();
+	for segment in path.split(['/', '\\']) {
+		match segment {
+			"" | "." => {},
+			".." if segments.last().is_some_and(|s| *s != "..") => { segments.pop(); },
+			_ => segments.push(segment),
+		}
+	}
+	segments.join("/")
+}
+
+/// Determines the type of `path` in a file system made up of the given (normalized) file paths.
+fn path_type_from_keys<'a> (mut keys: impl Iterator<Item=&'a str>, path: &str) -> Option<PathType>
+{
+	if path.is_empty() {
+		return Some(PathType::Directory);
+	}
+	keys.find_map(|key| {
+		if key == path {
+			Some(PathType::File)
+		} else {
+			key.strip_prefix(path).filter(|rest| rest.starts_with('/')).map(|_| PathType::Directory)
+		}
+	})
+}
+
+/// Lists the immediate children of the directory at `path` in a file system made up of the given (normalized) file
+/// paths.
+fn dir_entries_from_keys<'a> (keys: impl Iterator<Item=&'a str>, path: &str) -> Vec<(PathType, String)>
+{
+	let mut files = BTreeSet::new();
+	let mut dirs = BTreeSet::new();
+	for key in keys {
+		let rest = if path.is_empty() {
+			key
+		} else {
+			match key.strip_prefix(path).and_then(|rest| rest.strip_prefix('/')) {
+				Some(rest) => rest,
+				None => continue,
+			}
+		};
+		match rest.split_once('/') {
+			Some((dir, _)) => { dirs.insert(dir); },
+			None => { files.insert(rest); },
+		}
+	}
+	dirs.into_iter().map(|d| (PathType::Directory, d.to_owned()))
+		.chain(files.into_iter().map(|f| (PathType::File, f.to_owned())))
+		.collect()
+}
+
+/// Reads a path argument passed in by *Slang* and normalizes it.
+fn path_arg (path: *const c_char) -> Option<String> {
+	if path.is_null() {
+		return None;
+	}
+	let path = unsafe { CStr::from_ptr(path) };
+	Some(normalize_path(&path.to_string_lossy()))
+}
+
+/// Hands a string to *Slang* through a freshly allocated [`VecBlob`].
+fn write_string_blob (s: String, out_blob: *mut *mut sys::ISlangBlob) -> sys::SlangResult {
+	if out_blob.is_null() {
+		return E_INVALIDARG;
+	}
+	unsafe { *out_blob = VecBlob::from_string(s) as *mut sys::ISlangBlob; }
+	S_OK
+}
+
+
+
+//////
+//
+// COM endpoint implementations
+//
+
+////
+// Interface: IUnknown
+
+unsafe extern "C" fn query_interface (
+	this: *mut sys::ISlangUnknown,
+	uuid: *const sys::SlangUUID,
+	out_object: *mut *mut c_void,
+) -> sys::SlangResult {
+	if out_object.is_null() || uuid.is_null() {
+		return E_INVALIDARG;
+	}
+	let obj = BoxedFileSystem::this(this);
+
+	let iid = unsafe { &*uuid };
+	if eq_guid(iid, &IUnknown::IID) || eq_guid(iid, &ICASTABLE_IID) || eq_guid(iid, &IFILESYSTEM_IID)
+		|| eq_guid(iid, &BoxedFileSystem::IID) {
+		// Increase refcount for the returned interface
+		obj.ref_count.fetch_add(1, Ordering::Relaxed);
+		unsafe { *out_object = this as *mut c_void; }
+		S_OK
+	}
+	else {
+		unsafe { *out_object = std::ptr::null_mut() };
+		E_NOINTERFACE
+	}
+}
+
+unsafe extern "C" fn add_ref (this: *mut sys::ISlangUnknown) -> u32 {
+	let obj = BoxedFileSystem::this(this);
+	let prev = obj.ref_count.fetch_add(1, Ordering::Relaxed);
+	prev + 1
+}
+
+unsafe extern "C" fn release (this: *mut sys::ISlangUnknown) -> u32
+{
+	let obj = BoxedFileSystem::this(this);
+	let prev = obj.ref_count.fetch_sub(1, Ordering::Release);
+	if prev == 1 {
+		// Acquire to synchronize with potential writers before drop
+		std::sync::atomic::fence(Ordering::Acquire);
+		// Reconstruct the Box and drop
+		let _ = unsafe {
+			// Safety: we own the Box, and the Box is the only reference to it.
+			Box::from_raw(this as *mut BoxedFileSystem)
+		};
+		0
+	} else {
+		prev-1
+	}
+}
+
+
+////
+// Interface: ICastable
+
+unsafe extern "C" fn cast_as (this: *mut c_void, guid: *const sys::SlangUUID) -> *mut c_void {
+	if guid.is_null() {
+		return std::ptr::null_mut();
+	}
+	let iid = unsafe { &*guid };
+	if eq_guid(iid, &IUnknown::IID) || eq_guid(iid, &ICASTABLE_IID) || eq_guid(iid, &IFILESYSTEM_IID)
+		|| eq_guid(iid, &BoxedFileSystem::IID) {
+		// Casting does not hand out a new reference
+		this
+	} else {
+		std::ptr::null_mut()
+	}
+}
+
+
+////
+// Interface: ISlangFileSystem
+
+unsafe extern "C" fn load_file (this: *mut c_void, path: *const c_char, out_blob: *mut *mut sys::ISlangBlob)
+	-> sys::SlangResult
+{
+	let (Some(path), false) = (path_arg(path), out_blob.is_null()) else {
+		return E_INVALIDARG;
+	};
+	match BoxedFileSystem::this_void(this).inner.load_file(&path) {
+		Some(contents) => {
+			unsafe { *out_blob = VecBlob::from_vec(contents) as *mut sys::ISlangBlob; }
+			S_OK
+		},
+		None => {
+			unsafe { *out_blob = std::ptr::null_mut(); }
+			E_NOT_FOUND
+		}
+	}
+}
+
+
+////
+// Interface: ISlangFileSystemExt
+
+unsafe extern "C" fn get_file_unique_identity (
+	this: *mut c_void, path: *const c_char, out_unique_identity: *mut *mut sys::ISlangBlob
+) -> sys::SlangResult {
+	let Some(path) = path_arg(path) else {
+		return E_INVALIDARG;
+	};
+	// Normalized paths are unique within our virtual file system
+	match BoxedFileSystem::this_void(this).inner.path_type(&path) {
+		Some(_) => write_string_blob(path, out_unique_identity),
+		None => E_NOT_FOUND,
+	}
+}
+
+unsafe extern "C" fn calc_combined_path (
+	_this: *mut c_void, from_path_type: sys::SlangPathType, from_path: *const c_char, path: *const c_char,
+	path_out: *mut *mut sys::ISlangBlob
+) -> sys::SlangResult {
+	let (Some(from_path), Some(path)) = (path_arg(from_path), path_arg(path)) else {
+		return E_INVALIDARG;
+	};
+	let base = match from_path_type {
+		PathType::File => from_path.rsplit_once('/').map_or("", |(dir, _)| dir),
+		_ => from_path.as_str(),
+	};
+	write_string_blob(normalize_path(&format!("{base}/{path}")), path_out)
+}
+
+unsafe extern "C" fn get_path_type (this: *mut c_void, path: *const c_char, path_type_out: *mut sys::SlangPathType)
+	-> sys::SlangResult
+{
+	let (Some(path), false) = (path_arg(path), path_type_out.is_null()) else {
+		return E_INVALIDARG;
+	};
+	match BoxedFileSystem::this_void(this).inner.path_type(&path) {
+		Some(path_type) => {
+			unsafe { *path_type_out = path_type; }
+			S_OK
+		},
+		None => E_NOT_FOUND,
+	}
+}
+
+unsafe extern "C" fn get_path (
+	_this: *mut c_void, kind: sys::PathKind, path: *const c_char, out_path: *mut *mut sys::ISlangBlob
+) -> sys::SlangResult {
+	let Some(path) = path_arg(path) else {
+		return E_INVALIDARG;
+	};
+	match kind {
+		// Our paths don't map to anything on the operating system
+		sys::PathKind::OperatingSystem => E_NOT_IMPLEMENTED,
+		_ => write_string_blob(path, out_path),
+	}
+}
+
+unsafe extern "C" fn clear_cache (_this: *mut c_void) {
+	// We don't cache anything
+}
+
+unsafe extern "C" fn enumerate_path_contents (
+	this: *mut c_void, path: *const c_char, callback: sys::FileSystemContentsCallBack, user_data: *mut c_void
+) -> sys::SlangResult {
+	let (Some(path), Some(callback)) = (path_arg(path), callback) else {
+		return E_INVALIDARG;
+	};
+	let fs = BoxedFileSystem::this_void(this);
+	if fs.inner.path_type(&path) != Some(PathType::Directory) {
+		return E_NOT_FOUND;
+	}
+	for (path_type, name) in fs.inner.read_dir(&path) {
+		let Ok(name) = CString::new(name) else {
+			continue;
+		};
+		unsafe { callback(path_type, name.as_ptr(), user_data) };
+	}
+	S_OK
+}
+
+unsafe extern "C" fn get_os_path_kind (_this: *mut c_void) -> sys::OSPathKind {
+	sys::OSPathKind::None
+}
+
+
+////
+// Interface binding
+
+static VTABLE: sys::IFileSystemExtVtable = sys::IFileSystemExtVtable {
+	_base: sys::IFileSystemVtable {
+		_base: sys::ICastableVtable {
+			_base: sys::ISlangUnknown__bindgen_vtable {
+				ISlangUnknown_queryInterface: query_interface,
+				ISlangUnknown_addRef: add_ref,
+				ISlangUnknown_release: release,
+			},
+			castAs: cast_as,
+		},
+		loadFile: load_file,
+	},
+	getFileUniqueIdentity: get_file_unique_identity,
+	calcCombinedPath: calc_combined_path,
+	getPathType: get_path_type,
+	getPath: get_path,
+	clearCache: clear_cache,
+	enumeratePathContents: enumerate_path_contents,
+	getOSPathKind: get_os_path_kind,
+};
+
+
+
+//////
+//
+// Macros
+//
+
+/// Embeds a list of files below a directory into the binary via `include_bytes!`, producing the `'static` table
+/// expected by [`StaticFileSystem::new`]. The directory is resolved relative to the file invoking the macro, while the
+/// file paths become the paths under which the files are visible to *Slang*.
+///
+/// ```ignore
+/// static SHADERS: StaticFileSystem = StaticFileSystem::new(embed_files!("../shaders": "common.slang", "brdf.slang"));
+/// ```
+#[macro_export]
+macro_rules! embed_files {
+	($dir:literal: $($path:literal),* $(,)?) => {
+		&[$(($path, include_bytes!(concat!($dir, "/", $path)) as &[u8])),*]
+	};
+}
@@ -7,6 +7,12 @@ mod blob;
 #[allow(unused_imports)]
 pub use blob::{VecBlob, ImplementsISlangBlob}; // re-export
 
+mod file_system;
+#[allow(unused_imports)]
+pub use file_system::{
+	ShaderFileSystem, MemoryFileSystem, StaticFileSystem, BoxedFileSystem, normalize_path
+}; // re-export
+
 
 /// The `HRESULT` code for successful execution of a COM method.
 pub const S_OK: sys::SlangResult = sys::SLANG_OK as i32;
@@ -17,6 +23,12 @@ pub const E_INVALIDARG: sys::SlangResult  =  0x8007005 as i32;
 /// The `HRESULT` code indicating that the requested interface is not supported.
 pub const E_NOINTERFACE: sys::SlangResult = 0x80004002 as u32 as i32;
 
+/// The `HRESULT` code indicating that the requested functionality is not implemented.
+pub const E_NOT_IMPLEMENTED: sys::SlangResult = 0x80004001 as u32 as i32;
+
+/// The *Slang* result code indicating that a requested item (e.g. a file) could not be found.
+pub const E_NOT_FOUND: sys::SlangResult = 0x82000005 as u32 as i32;
+
 pub unsafe trait Object: Sized {
 	#[doc(hidden)]
 	type Vtable;
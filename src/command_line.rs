@@ -0,0 +1,372 @@
+//! Conversion between *slangc* command line arguments and session descriptions.
+
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+
+use crate::{
+	CompileTarget, CompilerOptionName, CompilerOptionValue, CompilerOptions, Error, FileSystemType, FloatingPointMode,
+	FpDenormalMode, GlobalSession, IUnknown, Interface, LineDirectiveMode, PassThrough, Result, SessionDesc,
+	SourceLanguage, Stage, TargetDesc, VulkanShiftKind, succeeded, sys, vcall,
+};
+
+/// A session description parsed from command line arguments by [`GlobalSession::parse_command_line`].
+///
+/// All strings and arrays the description refers to are owned by this struct, so the [`SessionDesc`] it hands out
+/// borrows from it.
+pub struct ParsedSessionDesc {
+	desc: sys::slang_SessionDesc,
+	_allocation: Option<IUnknown>,
+}
+
+impl GlobalSession {
+	/// Parses *slangc* style arguments, e.g. `["-target", "spirv", "-O3", "-DQUALITY=2"]`, into a session description.
+	/// Input files and output paths are not part of a session and are ignored.
+	pub fn parse_command_line(&self, args: &[&str]) -> Result<ParsedSessionDesc> {
+		let args = args.iter().map(|arg| CString::new(*arg)).collect::<std::result::Result<Vec<_>, _>>()
+			.map_err(|_| Error::Code(crate::SLANG_E_INVALID_ARG))?;
+		let arg_ptrs = args.iter().map(|arg| arg.as_ptr()).collect::<Vec<_>>();
+
+		let mut desc = *SessionDesc::default();
+		let mut allocation = null_mut();
+		let result = vcall!(self, parseCommandLineArguments(
+			arg_ptrs.len() as _,
+			arg_ptrs.as_ptr(),
+			&mut desc,
+			&mut allocation
+		));
+
+		let allocation = std::ptr::NonNull::new(allocation as *mut _).map(IUnknown);
+		if !succeeded(result) {
+			return Err(Error::Code(result));
+		}
+
+		Ok(ParsedSessionDesc { desc, _allocation: allocation })
+	}
+}
+
+impl ParsedSessionDesc {
+	/// The description to create a session from. More settings can be added through its builder methods.
+	pub fn session_desc(&self) -> SessionDesc<'_> {
+		SessionDesc { inner: self.desc, _phantom: PhantomData }
+	}
+
+	pub fn targets(&self) -> &[TargetDesc<'_>] {
+		// SAFETY: `TargetDesc` is a transparent wrapper around `slang_TargetDesc`
+		unsafe { raw_slice(self.desc.targets as *const TargetDesc, self.desc.targetCount as _) }
+	}
+
+	/// The options specific to target `index`.
+	pub fn target_options(&self, index: usize) -> CompilerOptions {
+		let target = &self.targets()[index];
+		unsafe { CompilerOptions::from_raw(target.compilerOptionEntries, target.compilerOptionEntryCount as _) }
+	}
+
+	/// The options for all targets.
+	pub fn options(&self) -> CompilerOptions {
+		unsafe { CompilerOptions::from_raw(self.desc.compilerOptionEntries, self.desc.compilerOptionEntryCount as _) }
+	}
+
+	pub fn search_paths(&self) -> impl ExactSizeIterator<Item = &str> {
+		unsafe { raw_slice(self.desc.searchPaths, self.desc.searchPathCount as _) }.iter()
+			.map(|&path| unsafe { CStr::from_ptr(path) }.to_str().unwrap_or_default())
+	}
+
+	pub fn preprocessor_macros(&self) -> impl ExactSizeIterator<Item = (&str, &str)> {
+		unsafe { raw_slice(self.desc.preprocessorMacros, self.desc.preprocessorMacroCount as _) }.iter().map(|m| {
+			let string = |s: *const i8| match s.is_null() {
+				true => "",
+				false => unsafe { CStr::from_ptr(s) }.to_str().unwrap_or_default(),
+			};
+			(string(m.name), string(m.value))
+		})
+	}
+}
+
+/// Like [`std::slice::from_raw_parts`], but accepts a null pointer for an empty slice.
+unsafe fn raw_slice<'a, T>(ptr: *const T, len: usize) -> &'a [T] {
+	match ptr.is_null() || len == 0 {
+		true => &[],
+		false => unsafe { std::slice::from_raw_parts(ptr, len) },
+	}
+}
+
+impl CompilerOptions {
+	/// Copies raw option entries, e.g. ones filled in by *Slang*.
+	unsafe fn from_raw(entries: *const sys::slang_CompilerOptionEntry, count: usize) -> Self {
+		let string = |s: *const i8| unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned();
+
+		unsafe { raw_slice(entries, count) }.iter().fold(Self::default(), |options, entry| {
+			let value = &entry.value;
+			let value = match value.kind {
+				sys::slang_CompilerOptionValueKind::Int => CompilerOptionValue::Int(value.intValue0, value.intValue1),
+				_ if value.stringValue0.is_null() => CompilerOptionValue::String(String::new()),
+				_ if value.stringValue1.is_null() => CompilerOptionValue::String(string(value.stringValue0)),
+				_ => CompilerOptionValue::Strings(string(value.stringValue0), string(value.stringValue1)),
+			};
+			options.push(entry.name, value)
+		})
+	}
+
+	/// Renders the options as *slangc* arguments, e.g. to reproduce a compilation from the command line.
+	///
+	/// Profiles and capabilities are left out, as their ids can't be mapped back to names, as are options without a
+	/// command line equivalent. Disabled flags are left out as well, since they are off by default.
+	pub fn to_command_line(&self) -> Vec<String> {
+		let mut args = Vec::new();
+		for entry in &self.entries {
+			command_line_args(entry.name, &entry.value, &mut args);
+		}
+		args
+	}
+}
+
+fn command_line_args(name: CompilerOptionName, value: &CompilerOptionValue, args: &mut Vec<String>) {
+	use CompilerOptionName as N;
+
+	let (i0, i1) = match value {
+		CompilerOptionValue::Int(i0, i1) => (*i0, *i1),
+		_ => (0, 0),
+	};
+	let (s0, s1) = match value {
+		CompilerOptionValue::Int(..) => ("", ""),
+		CompilerOptionValue::String(s0) => (s0.as_str(), ""),
+		CompilerOptionValue::Strings(s0, s1) => (s0.as_str(), s1.as_str()),
+	};
+	let mut push = |parts: &[&str]| args.extend(parts.iter().map(|part| part.to_string()));
+
+	let flag = match name {
+		N::MatrixLayoutColumn => "-matrix-layout-column-major",
+		N::MatrixLayoutRow => "-matrix-layout-row-major",
+		N::ZeroInitialize => "-zero-initialize",
+		N::IgnoreCapabilities => "-ignore-capabilities",
+		N::RestrictiveCapabilityCheck => "-restrictive-capability-check",
+		N::DumpWarningDiagnostics => "-dump-warning-diagnostics",
+		N::EmitIr => "-emit-ir",
+		N::ReportDownstreamTime => "-report-downstream-time",
+		N::ReportPerfBenchmark => "-report-perf-benchmark",
+		N::ReportCheckpointIntermediates => "-report-checkpoint-intermediates",
+		N::SkipSPIRVValidation => "-skip-spirv-validation",
+		N::DisableShortCircuit => "-disable-short-circuit",
+		N::MinimumSlangOptimization => "-minimum-slang-optimization",
+		N::DisableNonEssentialValidations => "-disable-non-essential-validations",
+		N::DisableSourceMap => "-disable-source-map",
+		N::UnscopedEnum => "-unscoped-enum",
+		N::PreserveParameters => "-preserve-params",
+		N::DefaultImageFormatUnknown => "-default-image-format-unknown",
+		N::DisableDynamicDispatch => "-disable-dynamic-dispatch",
+		N::DisableSpecialization => "-disable-specialization",
+		N::Obfuscate => "-obfuscate",
+		N::VulkanInvertY => "-fvk-invert-y",
+		N::VulkanUseDxPositionW => "-fvk-use-dx-position-w",
+		N::VulkanUseEntryPointName => "-fvk-use-entrypoint-name",
+		N::VulkanUseGLLayout => "-fvk-use-gl-layout",
+		N::VulkanEmitReflection => "-fspv-reflect",
+		N::ForceDXLayout => "-fvk-use-dx-layout",
+		N::GLSLForceScalarLayout => "-force-glsl-scalar-layout",
+		N::EnableEffectAnnotations => "-enable-effect-annotations",
+		N::EmitSpirvViaGLSL => "-emit-spirv-via-glsl",
+		N::EmitSpirvDirectly => "-emit-spirv-directly",
+		N::IncompleteLibrary => "-incomplete-library",
+		N::DumpReproOnError => "-dump-repro-on-error",
+		N::DumpAst => "-dump-ast",
+		N::DumpIntermediates => "-dump-intermediates",
+		N::DumpIr => "-dump-ir",
+		N::DumpIrIds => "-dump-ir-ids",
+		N::PreprocessorOutput => "-E",
+		N::OutputIncludes => "-output-includes",
+		N::SkipCodeGen => "-skip-codegen",
+		N::ValidateIr => "-validate-ir",
+		N::VerbosePaths => "-verbose-paths",
+		N::VerifyDebugSerialIr => "-verify-debug-serial-ir",
+		N::NoCodeGen => "-no-codegen",
+		N::Heterogeneous => "-heterogeneous",
+		N::NoMangle => "-no-mangle",
+		N::NoHLSLBinding => "-no-hlsl-binding",
+		N::NoHLSLPackConstantBufferElements => "-no-hlsl-pack-constant-buffer-elements",
+		N::ValidateUniformity => "-validate-uniformity",
+		N::AllowGLSL => "-allow-glsl",
+		N::EnableExperimentalPasses => "-enable-experimental-passes",
+		N::TrackLiveness => "-track-liveness",
+		N::LoopInversion => "-loop-inversion",
+		N::ParameterBlocksUseRegisterSpaces => "-parameter-blocks-use-register-spaces",
+		N::EnableExperimentalDynamicDispatch => "-enable-experimental-dynamic-dispatch",
+		N::GenerateWholeProgram => "-whole-program",
+		N::EmbedDownstreamIR => "-embed-downstream-ir",
+		N::SkipDownstreamLinking => "-skip-downstream-linking",
+		N::EmitSeparateDebug => "-separate-debug-info",
+		_ => "",
+	};
+	if !flag.is_empty() {
+		if i0 != 0 {
+			push(&[flag]);
+		}
+		return;
+	}
+
+	match name {
+		N::MacroDefine if s1.is_empty() => push(&[&format!("-D{s0}")]),
+		N::MacroDefine => push(&[&format!("-D{s0}={s1}")]),
+		N::Include => push(&["-I", s0]),
+		N::ModuleName => push(&["-module-name", s0]),
+		N::WarningsAsErrors => push(&["-warnings-as-errors", s0]),
+		N::DisableWarnings => push(&["-warnings-disable", s0]),
+		N::EnableWarning => push(&[&format!("-W{s0}")]),
+		N::DisableWarning => push(&[&format!("-Wno-{s0}")]),
+		N::SPIRVCoreGrammarJSON => push(&["-spirv-core-grammar", s0]),
+		N::DumpRepro => push(&["-dump-repro", s0]),
+		N::ReproFallbackDirectory => push(&["-repro-fallback-directory", s0]),
+		N::DumpIntermediatePrefix => push(&["-dump-intermediate-prefix", s0]),
+		N::ReferenceModule => push(&["-r", s0]),
+		N::TypeConformance => push(&["-conformance", s0]),
+		N::DownstreamArgs => {
+			for arg in s1.lines().filter(|arg| !arg.is_empty()) {
+				push(&[&format!("-X{s0}"), arg]);
+			}
+		}
+		N::Language => push(&["-lang", language_name(i0)]),
+		N::Stage => push(&["-stage", stage_name(i0)]),
+		N::Target => push(&["-target", target_name(i0)]),
+		N::Optimization => push(&[&format!("-O{i0}")]),
+		N::DebugInformation => push(&[&format!("-g{i0}")]),
+		N::FloatingPointMode => push(&["-fp-mode", match i0 {
+			i if i == FloatingPointMode::Fast as i32 => "fast",
+			i if i == FloatingPointMode::Precise as i32 => "precise",
+			_ => "default",
+		}]),
+		N::LineDirectiveMode => push(&["-line-directive-mode", match i0 {
+			i if i == LineDirectiveMode::None as i32 => "none",
+			i if i == LineDirectiveMode::Standard as i32 => "standard",
+			i if i == LineDirectiveMode::Glsl as i32 => "glsl",
+			i if i == LineDirectiveMode::SourceMap as i32 => "source-map",
+			_ => "default",
+		}]),
+		N::VulkanBindShift => {
+			push(&[&format!("-fvk-{}-shift", shift_kind_name(i0 >> 24)), &i1.to_string(), &(i0 & 0xffffff).to_string()]);
+		}
+		N::VulkanBindShiftAll => push(&[&format!("-fvk-{}-shift", shift_kind_name(i0)), &i1.to_string(), "all"]),
+		N::VulkanBindGlobals => push(&["-fvk-bind-globals", &i0.to_string(), &i1.to_string()]),
+		N::DefaultDownstreamCompiler => {
+			push(&["-default-downstream-compiler", language_name(i0), pass_through_name(i1)]);
+		}
+		N::PassThrough => push(&["-pass-through", pass_through_name(i0)]),
+		N::FileSystem => push(&["-file-system", match i0 {
+			i if i == FileSystemType::LoadFile as i32 => "load-file",
+			i if i == FileSystemType::Os as i32 => "os",
+			_ => "default",
+		}]),
+		N::BindlessSpaceIndex => push(&["-bindless-space-index", &i0.to_string()]),
+		N::LanguageVersion => push(&["-std", &i0.to_string()]),
+		N::EmitSpirvMethod => match i0 {
+			1 => push(&["-emit-spirv-via-glsl"]),
+			2 => push(&["-emit-spirv-directly"]),
+			_ => {}
+		},
+		N::DenormalModeFp16 | N::DenormalModeFp32 | N::DenormalModeFp64 => {
+			let flag = match name {
+				N::DenormalModeFp16 => "-denorm-mode-fp16",
+				N::DenormalModeFp32 => "-denorm-mode-fp32",
+				_ => "-denorm-mode-fp64",
+			};
+			push(&[flag, match i0 {
+				i if i == FpDenormalMode::Preserve as i32 => "preserve",
+				i if i == FpDenormalMode::FlushToZero as i32 => "ftz",
+				_ => "any",
+			}]);
+		}
+		_ => {}
+	}
+}
+
+fn language_name(language: i32) -> &'static str {
+	const NAMES: [(SourceLanguage, &str); 9] = [
+		(SourceLanguage::Slang, "slang"),
+		(SourceLanguage::Hlsl, "hlsl"),
+		(SourceLanguage::Glsl, "glsl"),
+		(SourceLanguage::C, "c"),
+		(SourceLanguage::Cpp, "cpp"),
+		(SourceLanguage::Cuda, "cuda"),
+		(SourceLanguage::Spirv, "spirv"),
+		(SourceLanguage::Metal, "metal"),
+		(SourceLanguage::Wgsl, "wgsl"),
+	];
+	NAMES.iter().find(|(l, _)| *l as i32 == language).map_or("unknown", |(_, name)| name)
+}
+
+fn stage_name(stage: i32) -> &'static str {
+	const NAMES: [(Stage, &str); 14] = [
+		(Stage::Vertex, "vertex"),
+		(Stage::Hull, "hull"),
+		(Stage::Domain, "domain"),
+		(Stage::Geometry, "geometry"),
+		(Stage::Fragment, "fragment"),
+		(Stage::Compute, "compute"),
+		(Stage::RayGeneration, "raygeneration"),
+		(Stage::Intersection, "intersection"),
+		(Stage::AnyHit, "anyhit"),
+		(Stage::ClosestHit, "closesthit"),
+		(Stage::Miss, "miss"),
+		(Stage::Callable, "callable"),
+		(Stage::Mesh, "mesh"),
+		(Stage::Amplification, "amplification"),
+	];
+	NAMES.iter().find(|(s, _)| *s as i32 == stage).map_or("unknown", |(_, name)| name)
+}
+
+fn target_name(target: i32) -> &'static str {
+	const NAMES: [(CompileTarget, &str); 23] = [
+		(CompileTarget::Glsl, "glsl"),
+		(CompileTarget::Hlsl, "hlsl"),
+		(CompileTarget::Spirv, "spirv"),
+		(CompileTarget::SpirvAsm, "spirv-asm"),
+		(CompileTarget::Dxbc, "dxbc"),
+		(CompileTarget::DxbcAsm, "dxbc-asm"),
+		(CompileTarget::Dxil, "dxil"),
+		(CompileTarget::DxilAsm, "dxil-asm"),
+		(CompileTarget::CSource, "c"),
+		(CompileTarget::CppSource, "cpp"),
+		(CompileTarget::HostExecutable, "exe"),
+		(CompileTarget::ShaderSharedLibrary, "sharedlib"),
+		(CompileTarget::ShaderHostCallable, "callable"),
+		(CompileTarget::CudaSource, "cuda"),
+		(CompileTarget::Ptx, "ptx"),
+		(CompileTarget::CudaObjectCode, "cuobj"),
+		(CompileTarget::HostCppSource, "host-cpp"),
+		(CompileTarget::Metal, "metal"),
+		(CompileTarget::MetalLib, "metallib"),
+		(CompileTarget::MetalLibAsm, "metallib-asm"),
+		(CompileTarget::Wgsl, "wgsl"),
+		(CompileTarget::WgslSpirvAsm, "wgsl-spirv-asm"),
+		(CompileTarget::WgslSpirv, "wgsl-spirv"),
+	];
+	NAMES.iter().find(|(t, _)| *t as i32 == target).map_or("unknown", |(_, name)| name)
+}
+
+fn pass_through_name(pass_through: i32) -> &'static str {
+	const NAMES: [(PassThrough, &str); 14] = [
+		(PassThrough::Fxc, "fxc"),
+		(PassThrough::Dxc, "dxc"),
+		(PassThrough::Glslang, "glslang"),
+		(PassThrough::SpirvDis, "spirv-dis"),
+		(PassThrough::Clang, "clang"),
+		(PassThrough::VisualStudio, "visualstudio"),
+		(PassThrough::Gcc, "gcc"),
+		(PassThrough::GenericCCpp, "genericcpp"),
+		(PassThrough::Nvrtc, "nvrtc"),
+		(PassThrough::Llvm, "llvm"),
+		(PassThrough::SpirvOpt, "spirv-opt"),
+		(PassThrough::Metal, "metal"),
+		(PassThrough::Tint, "tint"),
+		(PassThrough::SpirvLink, "spirv-link"),
+	];
+	NAMES.iter().find(|(p, _)| *p as i32 == pass_through).map_or("none", |(_, name)| name)
+}
+
+fn shift_kind_name(kind: i32) -> &'static str {
+	match kind {
+		k if k == VulkanShiftKind::ConstantBuffer as i32 => "b",
+		k if k == VulkanShiftKind::ShaderResource as i32 => "t",
+		k if k == VulkanShiftKind::UnorderedAccess as i32 => "u",
+		_ => "s",
+	}
+}
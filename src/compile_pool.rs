@@ -0,0 +1,172 @@
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread::JoinHandle;
+
+use crate::reflection::ProgramReflection;
+use crate::pipeline::{link_and_generate, session_config};
+use crate::{CompileTarget, CompilerOptions, Diagnostics, Error, GlobalSession, Result, SLANG_E_NOT_FOUND, SLANG_FAIL};
+
+/// A single entry point to compile on a [`CompilePool`].
+#[derive(Clone)]
+pub struct CompileJob {
+	pub module_name: String,
+	/// The path reported in diagnostics and used to resolve relative imports.
+	pub path: String,
+	pub source: String,
+	pub entry_point: String,
+	pub target: CompileTarget,
+	pub profile: Option<String>,
+	pub search_paths: Vec<String>,
+	pub options: CompilerOptions,
+}
+
+impl CompileJob {
+	pub fn new(module_name: &str, source: &str, entry_point: &str, target: CompileTarget) -> Self {
+		Self {
+			module_name: module_name.to_owned(),
+			path: format!("{module_name}.slang"),
+			source: source.to_owned(),
+			entry_point: entry_point.to_owned(),
+			target,
+			profile: None,
+			search_paths: Vec::new(),
+			options: CompilerOptions::default(),
+		}
+	}
+
+	pub fn path(mut self, path: &str) -> Self {
+		self.path = path.to_owned();
+		self
+	}
+
+	pub fn profile(mut self, profile: &str) -> Self {
+		self.profile = Some(profile.to_owned());
+		self
+	}
+
+	pub fn search_path(mut self, path: &str) -> Self {
+		self.search_paths.push(path.to_owned());
+		self
+	}
+
+	pub fn options(mut self, options: CompilerOptions) -> Self {
+		self.options = options;
+		self
+	}
+}
+
+/// The result of a [`CompileJob`], detached from any *Slang* object.
+#[derive(Debug, Clone)]
+pub struct CompiledShader {
+	pub code: Vec<u8>,
+	/// Warnings reported while compiling.
+	pub diagnostics: Diagnostics,
+	pub reflection: ProgramReflection,
+}
+
+/// A pending [`CompileJob`] submitted to a [`CompilePool`].
+pub struct CompileHandle(mpsc::Receiver<Result<CompiledShader>>);
+
+impl CompileHandle {
+	/// Blocks until the job has finished.
+	pub fn wait(self) -> Result<CompiledShader> {
+		self.0.recv().unwrap_or(Err(Error::Code(SLANG_FAIL)))
+	}
+}
+
+type Task = (CompileJob, mpsc::Sender<Result<CompiledShader>>);
+
+/// Compiles shaders on a fixed set of worker threads.
+///
+/// Each worker owns its own [`GlobalSession`], so no *Slang* object ever crosses a thread boundary. Jobs and results
+/// are plain Rust data.
+pub struct CompilePool {
+	sender: Option<mpsc::Sender<Task>>,
+	workers: Vec<JoinHandle<()>>,
+}
+
+impl CompilePool {
+	/// Spawns `worker_count` worker threads, at least one.
+	pub fn new(worker_count: usize) -> Self {
+		let (sender, receiver) = mpsc::channel::<Task>();
+		let receiver = Arc::new(Mutex::new(receiver));
+
+		let workers = (0..worker_count.max(1)).map(|index| {
+			let receiver = receiver.clone();
+			std::thread::Builder::new()
+				.name(format!("slang-compile-{index}"))
+				.spawn(move || worker(&receiver))
+				.unwrap()
+		}).collect();
+
+		Self { sender: Some(sender), workers }
+	}
+
+	/// Spawns one worker per available CPU core.
+	pub fn with_available_parallelism() -> Self {
+		Self::new(std::thread::available_parallelism().map_or(1, |n| n.get()))
+	}
+
+	pub fn worker_count(&self) -> usize {
+		self.workers.len()
+	}
+
+	pub fn submit(&self, job: CompileJob) -> CompileHandle {
+		let (result_sender, result_receiver) = mpsc::channel();
+		// The workers only stop once the pool is dropped, so sending can't fail
+		self.sender.as_ref().unwrap().send((job, result_sender)).unwrap();
+		CompileHandle(result_receiver)
+	}
+
+	/// Compiles all jobs in parallel and returns their results in submission order.
+	pub fn compile_all(&self, jobs: impl IntoIterator<Item = CompileJob>) -> Vec<Result<CompiledShader>> {
+		let handles = jobs.into_iter().map(|job| self.submit(job)).collect::<Vec<_>>();
+		handles.into_iter().map(CompileHandle::wait).collect()
+	}
+}
+
+impl Drop for CompilePool {
+	fn drop(&mut self) {
+		// Closing the channel makes the workers exit once the queue is drained
+		drop(self.sender.take());
+		for worker in self.workers.drain(..) {
+			let _ = worker.join();
+		}
+	}
+}
+
+fn worker(receiver: &Mutex<mpsc::Receiver<Task>>) {
+	let global_session = GlobalSession::new();
+
+	loop {
+		let task = receiver.lock().unwrap().recv();
+		let Ok((job, result_sender)) = task else {
+			return;
+		};
+
+		let result = match &global_session {
+			Some(global_session) => std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+				compile(global_session, &job)
+			})).unwrap_or(Err(Error::Code(SLANG_FAIL))),
+			None => Err(Error::Code(SLANG_FAIL)),
+		};
+		let _ = result_sender.send(result);
+	}
+}
+
+fn compile(global_session: &GlobalSession, job: &CompileJob) -> Result<CompiledShader> {
+	let targets = [(job.target, job.profile.as_deref())];
+	let session = session_config(targets, &job.search_paths, &job.options).create_session(global_session)?;
+
+	let (module, mut diagnostics) =
+		session.load_module_from_source_string_with_diagnostics(&job.module_name, &job.path, &job.source)?;
+	let entry_point = module.find_entry_point_by_name(&job.entry_point).ok_or(Error::Code(SLANG_E_NOT_FOUND))?;
+	let program = session.create_composite_component_type(&[module.into(), entry_point.into()])?;
+
+	let mut generated = link_and_generate(&program, 1, 1, &mut diagnostics)?;
+
+	Ok(CompiledShader {
+		code: generated.code.swap_remove(0).swap_remove(0),
+		reflection: ProgramReflection::new(generated.linked_program.layout(0)?),
+		diagnostics,
+	})
+}
@@ -0,0 +1,89 @@
+//! Precompiled core modules, which spare [`GlobalSession::new`] from compiling the core module on every start.
+//!
+//! ```ignore
+//! // Compiled on the first run and loaded from the file afterwards
+//! let global_session = slang::GlobalSession::with_cached_core_module("cache/slang-core-module.bin")?;
+//!
+//! // Or saved by a build script with `slang::build::core_module` and embedded in the binary
+//! static CORE_MODULE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/slang-core-module.bin"));
+//! let global_session = slang::GlobalSession::with_core_module(CORE_MODULE)?;
+//! ```
+
+use std::path::Path;
+use std::ptr::null_mut;
+
+use crate::{ArchiveType, Blob, Error, GlobalSession, IUnknown, Interface, Result, SLANG_FAIL, succeeded, vcall};
+
+impl GlobalSession {
+	/// A global session with the core module loaded from an archive saved by
+	/// [`save_core_module`](Self::save_core_module). Fails if the archive was saved by a different version of *Slang*.
+	pub fn with_core_module(archive: &[u8]) -> Result<GlobalSession> {
+		let global_session = GlobalSession::new_without_core_module().ok_or(Error::Code(SLANG_FAIL))?;
+		global_session.load_core_module(archive)?;
+		Ok(global_session)
+	}
+
+	/// A global session with the core module loaded from the archive at `path`. If there is no archive or it can't be
+	/// loaded, e.g. because it was saved by a different version of *Slang*, the core module is compiled and the
+	/// archive is written for the next start. Failing to write it is not an error.
+	pub fn with_cached_core_module(path: impl AsRef<Path>) -> Result<GlobalSession> {
+		let path = path.as_ref();
+		let cached = std::fs::read(path).ok().and_then(|archive| Self::with_core_module(&archive).ok());
+		if let Some(global_session) = cached {
+			return Ok(global_session);
+		}
+
+		let global_session = GlobalSession::new_without_core_module().ok_or(Error::Code(SLANG_FAIL))?;
+		global_session.compile_core_module()?;
+		if let Ok(archive) = global_session.save_core_module(ArchiveType::RiffLz4) {
+			let _ = write_archive(path, archive.as_slice());
+		}
+		Ok(global_session)
+	}
+
+	/// Compiles the core module into a global session created by
+	/// [`new_without_core_module`](Self::new_without_core_module).
+	pub fn compile_core_module(&self) -> Result<()> {
+		let result = vcall!(self, compileCoreModule(0));
+		if !succeeded(result) {
+			return Err(Error::Code(result));
+		}
+		Ok(())
+	}
+
+	/// Loads the core module into a global session created by
+	/// [`new_without_core_module`](Self::new_without_core_module).
+	pub fn load_core_module(&self, archive: &[u8]) -> Result<()> {
+		let result = vcall!(self, loadCoreModule(archive.as_ptr() as *const _, archive.len()));
+		if !succeeded(result) {
+			return Err(Error::Code(result));
+		}
+		Ok(())
+	}
+
+	/// Serializes the core module for [`load_core_module`](Self::load_core_module).
+	pub fn save_core_module(&self, archive_type: ArchiveType) -> Result<Blob> {
+		let mut blob = null_mut();
+		let result = vcall!(self, saveCoreModule(archive_type, &mut blob));
+		if !succeeded(result) {
+			return Err(Error::Code(result));
+		}
+		Ok(Blob(IUnknown(std::ptr::NonNull::new(blob as *mut _).ok_or(Error::Code(SLANG_FAIL))?)))
+	}
+}
+
+/// Writes a core module archive to `path` through a temporary file, so that processes starting at the same time never
+/// load a partially written archive.
+pub(crate) fn write_archive(path: &Path, archive: &[u8]) -> std::io::Result<()> {
+	if let Some(dir) = path.parent() {
+		std::fs::create_dir_all(dir)?;
+	}
+	let mut temp_path = path.as_os_str().to_owned();
+	temp_path.push(format!(".{}.tmp", std::process::id()));
+
+	let result = std::fs::write(&temp_path, archive).and_then(|()| std::fs::rename(&temp_path, path));
+	if result.is_err() {
+		let _ = std::fs::remove_file(&temp_path);
+	}
+	result
+}
@@ -0,0 +1,248 @@
+use crate::{Blob, IUnknown, sys};
+
+/// The severity of a single [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+	Note,
+	Warning,
+	Error,
+	Fatal,
+	Internal,
+}
+
+impl Severity {
+	/// The keywords *Slang* uses for each severity, longest first so that prefix matching is unambiguous.
+	const KEYWORDS: [(&'static str, Severity); 5] = [
+		("internal error", Severity::Internal),
+		("fatal error", Severity::Fatal),
+		("warning", Severity::Warning),
+		("error", Severity::Error),
+		("note", Severity::Note),
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Severity::Note => "note",
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+			Severity::Fatal => "fatal error",
+			Severity::Internal => "internal error",
+		}
+	}
+
+	/// Whether a diagnostic of this severity makes the operation that produced it fail.
+	pub fn is_error(&self) -> bool {
+		*self >= Severity::Error
+	}
+}
+
+impl std::fmt::Display for Severity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// The source location a [`Diagnostic`] refers to. Lines and columns are 1-based.
+///
+/// *Slang* only reports the line in the diagnostic header by default. The column range is recovered from the
+/// `^~~~` marker printed below the offending source line whenever one is present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+	pub line: u32,
+	pub column: Option<u32>,
+	/// Column one past the last character highlighted by the diagnostic.
+	pub end_column: Option<u32>,
+}
+
+/// A single diagnostic message reported by *Slang*.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	/// The *Slang* diagnostic code, e.g. `30015` for an undefined identifier.
+	pub code: Option<u32>,
+	pub path: Option<String>,
+	pub span: Option<Span>,
+	pub message: String,
+}
+
+impl Diagnostic {
+	/// The diagnostic code in the `E30015`/`W30081` notation, if there is one.
+	pub fn code_string(&self) -> Option<String> {
+		let prefix = match self.severity {
+			Severity::Note => 'N',
+			Severity::Warning => 'W',
+			_ => 'E',
+		};
+		self.code.map(|code| format!("{prefix}{code}"))
+	}
+
+	/// Tries to parse a diagnostic header line of the form `path(line[,column]): severity [code]: message`. The
+	/// location part is optional.
+	fn parse_header(line: &str) -> Option<Diagnostic> {
+		let (location, rest) = match Severity::KEYWORDS.iter().any(|(kw, _)| line.starts_with(kw)) {
+			true => (None, line),
+			false => {
+				let idx = line.find("): ")?;
+				(Some(&line[..=idx]), &line[idx + 3..])
+			}
+		};
+
+		let (severity, rest) = Severity::KEYWORDS
+			.iter()
+			.find_map(|(kw, severity)| rest.strip_prefix(kw).map(|rest| (*severity, rest)))?;
+		let (code, message) = rest.split_once(':')?;
+		let code = match code.trim() {
+			"" => None,
+			code => Some(code.parse().ok()?),
+		};
+
+		let (path, span) = match location.and_then(|l| l.strip_suffix(')')?.rsplit_once('(')) {
+			Some((path, position)) => {
+				let mut numbers = position.split(',').map(|n| n.trim().parse::<u32>());
+				let line = numbers.next()?.ok()?;
+				let column = numbers.next().transpose().ok()?;
+				let path = (!path.is_empty()).then(|| path.to_owned());
+				// Slang uses line 0 for diagnostics that don't refer to any source location
+				(path, (line > 0).then_some(Span { line, column, end_column: None }))
+			}
+			None => (None, None),
+		};
+
+		Some(Diagnostic { severity, code, path, span, message: message.trim().to_owned() })
+	}
+}
+
+impl std::fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if let Some(path) = &self.path {
+			write!(f, "{path}")?;
+		}
+		match self.span {
+			Some(Span { line, column: Some(column), .. }) => write!(f, "({line}, {column}): ")?,
+			Some(Span { line, .. }) => write!(f, "({line}): ")?,
+			None if self.path.is_some() => write!(f, ": ")?,
+			None => {}
+		}
+		write!(f, "{}", self.severity)?;
+		if let Some(code) = self.code {
+			write!(f, " {code}")?;
+		}
+		write!(f, ": {}", self.message)
+	}
+}
+
+/// The diagnostic output of a *Slang* operation, both as the raw text and parsed into individual [`Diagnostic`]s.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+	text: String,
+	entries: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+	/// Parses the textual diagnostic output produced by *Slang*. Lines that are not part of any recognizable
+	/// diagnostic are skipped, but remain available through [`text`](Self::text).
+	pub fn parse(text: &str) -> Self {
+		let mut entries: Vec<Diagnostic> = Vec::new();
+		let mut previous_line = "";
+
+		for line in text.lines() {
+			if let Some(diagnostic) = Diagnostic::parse_header(line) {
+				entries.push(diagnostic);
+			} else if let Some(span) = entries.last_mut().and_then(|d| d.span.as_mut()) {
+				// Recover the column range from a `^~~~` marker below the quoted source line
+				let marker = line.trim_end();
+				if !previous_line.is_empty() && marker.contains('^') && marker.trim_start().chars().all(|c| c == '^' || c == '~') {
+					let start = marker.chars().take_while(|c| c.is_whitespace()).count() as u32 + 1;
+					let length = marker.trim_start().chars().count() as u32;
+					span.column.get_or_insert(start);
+					span.end_column.get_or_insert(start + length);
+				}
+			}
+			previous_line = line;
+		}
+
+		Self { text: text.to_owned(), entries }
+	}
+
+	/// Takes ownership of a diagnostics blob returned by *Slang* (which may be null) and parses it.
+	pub(crate) fn from_raw(blob: *mut sys::slang_IBlob) -> Self {
+		match std::ptr::NonNull::new(blob as *mut _) {
+			Some(blob) => Self::from_blob(&Blob(IUnknown(blob))),
+			None => Self::default(),
+		}
+	}
+
+	pub fn from_blob(blob: &Blob) -> Self {
+		Self::parse(&String::from_utf8_lossy(blob.as_slice()))
+	}
+
+	/// The unparsed diagnostic output.
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	pub fn as_slice(&self) -> &[Diagnostic] {
+		&self.entries
+	}
+
+	pub fn into_vec(self) -> Vec<Diagnostic> {
+		self.entries
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty() && self.text.trim().is_empty()
+	}
+
+	pub fn has_errors(&self) -> bool {
+		self.entries.iter().any(|d| d.severity.is_error())
+	}
+
+	pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+		self.entries.iter().filter(|d| d.severity.is_error())
+	}
+
+	pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+		self.entries.iter().filter(|d| d.severity == Severity::Warning)
+	}
+
+	/// Appends the diagnostics of another operation to this one.
+	pub fn extend(&mut self, other: Diagnostics) {
+		if !self.text.is_empty() && !other.text.is_empty() && !self.text.ends_with('\n') {
+			self.text.push('\n');
+		}
+		self.text.push_str(&other.text);
+		self.entries.extend(other.entries);
+	}
+}
+
+impl std::ops::Deref for Diagnostics {
+	type Target = [Diagnostic];
+
+	fn deref(&self) -> &Self::Target {
+		&self.entries
+	}
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+	type Item = &'a Diagnostic;
+	type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.entries.iter()
+	}
+}
+
+impl IntoIterator for Diagnostics {
+	type Item = Diagnostic;
+	type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.entries.into_iter()
+	}
+}
+
+impl std::fmt::Display for Diagnostics {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.text.trim_end())
+	}
+}
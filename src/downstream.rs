@@ -0,0 +1,141 @@
+//! Selecting and probing the compilers *Slang* hands its output to, e.g. *dxc* for DXIL or *gcc* for host code.
+
+use std::ffi::CString;
+
+use crate::{
+	CompileTarget, Error, GlobalSession, Interface, PassThrough, Result, SLANG_E_INVALID_ARG, SourceLanguage, succeeded,
+	vcall,
+};
+
+/// Every pass-through compiler *Slang* knows about, i.e. all [`PassThrough`] values except `None`.
+pub const PASS_THROUGHS: [PassThrough; PassThrough::CountOf as usize - 1] = [
+	PassThrough::Fxc,
+	PassThrough::Dxc,
+	PassThrough::Glslang,
+	PassThrough::SpirvDis,
+	PassThrough::Clang,
+	PassThrough::VisualStudio,
+	PassThrough::Gcc,
+	PassThrough::GenericCCpp,
+	PassThrough::Nvrtc,
+	PassThrough::Llvm,
+	PassThrough::SpirvOpt,
+	PassThrough::Metal,
+	PassThrough::Tint,
+	PassThrough::SpirvLink,
+];
+
+// The length follows `CountOf`, and the values have to be in enum order, so a new compiler can't be missed
+const _: () = {
+	let mut index = 0;
+	while index < PASS_THROUGHS.len() {
+		assert!(PASS_THROUGHS[index] as usize == index + 1);
+		index += 1;
+	}
+};
+
+impl GlobalSession {
+	/// Makes *Slang* load `compiler` from `path` instead of searching for it. `path` is the directory containing the
+	/// compiler, or its shared library for compilers that are loaded as one, e.g. *dxc*. Fails if `path` contains a NUL
+	/// character.
+	pub fn set_downstream_compiler_path(&self, compiler: PassThrough, path: &str) -> Result<()> {
+		let path = CString::new(path).map_err(|_| Error::Code(SLANG_E_INVALID_ARG))?;
+		vcall!(self, setDownstreamCompilerPath(compiler, path.as_ptr()));
+		Ok(())
+	}
+
+	/// The compiler used for source code in `language`, e.g. which C++ compiler builds host callable code.
+	pub fn set_default_downstream_compiler(&self, language: SourceLanguage, compiler: PassThrough) -> Result<()> {
+		let result = vcall!(self, setDefaultDownstreamCompiler(language, compiler));
+		if !succeeded(result) {
+			return Err(Error::Code(result));
+		}
+		Ok(())
+	}
+
+	pub fn default_downstream_compiler(&self, language: SourceLanguage) -> PassThrough {
+		vcall!(self, getDefaultDownstreamCompiler(language))
+	}
+
+	/// The compiler producing `target` from code *Slang* generated as `source`, e.g. *dxc* or *fxc* for
+	/// [`CompileTarget::Hlsl`] to [`CompileTarget::Dxil`]. [`PassThrough::None`] restores the default.
+	pub fn set_downstream_compiler_for_transition(
+		&self,
+		source: CompileTarget,
+		target: CompileTarget,
+		compiler: PassThrough,
+	) {
+		vcall!(self, setDownstreamCompilerForTransition(source, target, compiler));
+	}
+
+	/// The compiler set for a transition, or [`PassThrough::None`] if *Slang* picks one itself.
+	pub fn downstream_compiler_for_transition(&self, source: CompileTarget, target: CompileTarget) -> PassThrough {
+		vcall!(self, getDownstreamCompilerForTransition(source, target))
+	}
+
+	/// Whether `compiler` can be found and loaded.
+	pub fn check_pass_through_support(&self, compiler: PassThrough) -> bool {
+		succeeded(vcall!(self, checkPassThroughSupport(compiler)))
+	}
+
+	/// Whether everything needed to compile for `target` is available, including its downstream compilers.
+	pub fn check_compile_target_support(&self, target: CompileTarget) -> bool {
+		succeeded(vcall!(self, checkCompileTargetSupport(target)))
+	}
+
+	/// Makes *Slang* use the SPIR-V grammar at `json_path` instead of the one built into it, e.g. to emit instructions
+	/// of a newer SPIR-V version.
+	pub fn set_spirv_core_grammar(&self, json_path: &str) -> Result<()> {
+		let json_path = CString::new(json_path).map_err(|_| Error::Code(SLANG_E_INVALID_ARG))?;
+		let result = vcall!(self, setSPIRVCoreGrammar(json_path.as_ptr()));
+		if !succeeded(result) {
+			return Err(Error::Code(result));
+		}
+		Ok(())
+	}
+
+	/// Probes which pass-through compilers are available, e.g. to report the capabilities of a build machine at
+	/// startup. This loads every compiler that can be found, which takes a moment.
+	pub fn downstream_compilers(&self) -> DownstreamCompilers {
+		DownstreamCompilers {
+			compilers: PASS_THROUGHS.iter()
+				.map(|&compiler| (compiler, self.check_pass_through_support(compiler)))
+				.collect(),
+		}
+	}
+}
+
+/// The availability of every pass-through compiler, see [`GlobalSession::downstream_compilers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownstreamCompilers {
+	compilers: Vec<(PassThrough, bool)>,
+}
+
+impl DownstreamCompilers {
+	pub fn is_available(&self, compiler: PassThrough) -> bool {
+		self.compilers.iter().any(|&(c, available)| c == compiler && available)
+	}
+
+	pub fn available(&self) -> impl Iterator<Item = PassThrough> {
+		self.compilers.iter().filter(|(_, available)| *available).map(|(compiler, _)| *compiler)
+	}
+
+	pub fn unavailable(&self) -> impl Iterator<Item = PassThrough> {
+		self.compilers.iter().filter(|(_, available)| !*available).map(|(compiler, _)| *compiler)
+	}
+
+	/// All compilers and whether they are available.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (PassThrough, bool)> {
+		self.compilers.iter().copied()
+	}
+}
+
+impl std::fmt::Display for DownstreamCompilers {
+	/// One line per compiler, e.g. `Dxc: available`.
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (compiler, available) in self.iter() {
+			writeln!(f, "{compiler:?}: {}", if available { "available" } else { "not found" })?;
+		}
+		Ok(())
+	}
+}
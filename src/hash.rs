@@ -0,0 +1,17 @@
+//! FNV-1a hashes, which are stable across Rust versions and platforms unlike the std hashers, so they can name files
+//! and cache entries.
+
+/// The bytes of all parts, each preceded by its length, which keeps `["ab", "c"]` and `["a", "bc"]` apart.
+fn length_prefixed<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> impl Iterator<Item = u8> {
+	parts.into_iter().flat_map(|part| (part.len() as u64).to_le_bytes().into_iter().chain(part.iter().copied()))
+}
+
+pub(crate) fn fnv1a_64<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+	length_prefixed(parts).fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x00000100000001b3))
+}
+
+pub(crate) fn fnv1a_128<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u128 {
+	length_prefixed(parts).fold(0x6c62272e07bb014262b821756295c58d, |hash, byte| {
+		(hash ^ byte as u128).wrapping_mul(0x0000000001000000000000000000013b)
+	})
+}
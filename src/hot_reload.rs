@@ -0,0 +1,215 @@
+//! Recompiling programs when their source files change.
+//!
+//! ```ignore
+//! let mut watcher = slang::hot_reload::ShaderWatcher::new().unwrap();
+//! let (id, program) = watcher.add(
+//!     WatchedProgram::new("lighting").target(slang::CompileTarget::Spirv, None).search_path("shaders")
+//! )?;
+//! upload(&program);
+//!
+//! // Once per frame
+//! for reload in watcher.poll() {
+//!     match reload.result {
+//!         Ok(program) => upload(&program),
+//!         // The previous version stays in place until the error is fixed
+//!         Err(error) => eprintln!("{error}"),
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::reflection::ProgramReflection;
+use crate::pipeline::{link_and_generate, session_config};
+use crate::{
+	CompileTarget, CompilerOptions, ComponentType, Diagnostics, Error, GlobalSession, Result, SLANG_E_NOT_FOUND,
+};
+
+/// A program to compile and keep up to date. Every entry point is compiled for every target.
+#[derive(Clone)]
+pub struct WatchedProgram {
+	/// The module to load, as passed to [`Session::load_module`](crate::Session::load_module).
+	pub module: String,
+	/// The entry points to compile, all entry points defined in the module if empty.
+	pub entry_points: Vec<String>,
+	pub targets: Vec<(CompileTarget, Option<String>)>,
+	pub search_paths: Vec<String>,
+	pub options: CompilerOptions,
+}
+
+impl WatchedProgram {
+	pub fn new(module: &str) -> Self {
+		Self {
+			module: module.to_owned(),
+			entry_points: Vec::new(),
+			targets: Vec::new(),
+			search_paths: Vec::new(),
+			options: CompilerOptions::default(),
+		}
+	}
+
+	pub fn entry_point(mut self, name: &str) -> Self {
+		self.entry_points.push(name.to_owned());
+		self
+	}
+
+	pub fn target(mut self, format: CompileTarget, profile: Option<&str>) -> Self {
+		self.targets.push((format, profile.map(str::to_owned)));
+		self
+	}
+
+	pub fn search_path(mut self, path: &str) -> Self {
+		self.search_paths.push(path.to_owned());
+		self
+	}
+
+	pub fn options(mut self, options: CompilerOptions) -> Self {
+		self.options = options;
+		self
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgramId(usize);
+
+/// The code and reflection of a [`WatchedProgram`] for one target.
+#[derive(Debug, Clone)]
+pub struct TargetOutput {
+	/// The code of every entry point, in the order of [`CompiledProgram::entry_points`].
+	pub code: Vec<Vec<u8>>,
+	pub reflection: ProgramReflection,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompiledProgram {
+	pub entry_points: Vec<String>,
+	/// One output per target, in the order they were added to the [`WatchedProgram`].
+	pub targets: Vec<TargetOutput>,
+	/// Warnings reported while compiling.
+	pub diagnostics: Diagnostics,
+	/// The files the program was compiled from, which are watched for changes.
+	pub dependencies: Vec<PathBuf>,
+}
+
+/// The outcome of recompiling a program after one of its files changed.
+pub struct Reload {
+	pub id: ProgramId,
+	/// The new program, or the error that kept the previous one in place.
+	pub result: Result<CompiledProgram>,
+}
+
+struct Watched {
+	desc: WatchedProgram,
+	program: CompiledProgram,
+}
+
+/// Compiles programs and recompiles them whenever one of the files they depend on changes.
+///
+/// Changes are detected by comparing file modification times in [`poll`](Self::poll), which is cheap enough to call
+/// once per frame for a few hundred files. Every recompilation uses a fresh session, so no stale module survives a
+/// change.
+pub struct ShaderWatcher {
+	global_session: GlobalSession,
+	programs: Vec<Watched>,
+	modified: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+impl ShaderWatcher {
+	pub fn new() -> Option<Self> {
+		Some(Self::with_global_session(GlobalSession::new()?))
+	}
+
+	pub fn with_global_session(global_session: GlobalSession) -> Self {
+		Self { global_session, programs: Vec::new(), modified: HashMap::new() }
+	}
+
+	/// Compiles a program and starts watching the files it depends on. Nothing is watched if compilation fails.
+	pub fn add(&mut self, desc: WatchedProgram) -> Result<(ProgramId, CompiledProgram)> {
+		let program = compile(&self.global_session, &desc)?;
+		self.watch(&program.dependencies);
+
+		let id = ProgramId(self.programs.len());
+		self.programs.push(Watched { desc, program: program.clone() });
+		Ok((id, program))
+	}
+
+	/// The latest successfully compiled version of a program.
+	pub fn program(&self, id: ProgramId) -> &CompiledProgram {
+		&self.programs[id.0].program
+	}
+
+	/// Recompiles all programs depending on files that changed since the last call.
+	pub fn poll(&mut self) -> Vec<Reload> {
+		let changed = self.modified.iter_mut().filter_map(|(path, modified)| {
+			let current = modified_time(path);
+			(current != *modified).then(|| {
+				*modified = current;
+				path.clone()
+			})
+		}).collect::<Vec<_>>();
+
+		if changed.is_empty() {
+			return Vec::new();
+		}
+
+		let mut reloads = Vec::new();
+		for index in 0..self.programs.len() {
+			if !self.programs[index].program.dependencies.iter().any(|path| changed.contains(path)) {
+				continue;
+			}
+
+			let result = compile(&self.global_session, &self.programs[index].desc);
+			if let Ok(program) = &result {
+				// A change may have added imports
+				self.watch(&program.dependencies);
+				self.programs[index].program = program.clone();
+			}
+			reloads.push(Reload { id: ProgramId(index), result });
+		}
+
+		reloads
+	}
+
+	fn watch(&mut self, paths: &[PathBuf]) {
+		for path in paths {
+			self.modified.entry(path.clone()).or_insert_with(|| modified_time(path));
+		}
+	}
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+	std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn compile(global_session: &GlobalSession, desc: &WatchedProgram) -> Result<CompiledProgram> {
+	let targets = desc.targets.iter().map(|(format, profile)| (*format, profile.as_deref()));
+	let session = session_config(targets, &desc.search_paths, &desc.options).create_session(global_session)?;
+
+	let (module, mut diagnostics) = session.load_module_with_diagnostics(&desc.module)?;
+	let dependencies = module.dependency_file_paths().map(PathBuf::from).collect();
+
+	let entry_points = match desc.entry_points.is_empty() {
+		true => module.entry_points().collect::<Vec<_>>(),
+		false => desc.entry_points.iter()
+			.map(|name| module.find_entry_point_by_name(name).ok_or(Error::Code(SLANG_E_NOT_FOUND)))
+			.collect::<Result<_>>()?,
+	};
+	let entry_point_names = entry_points.iter()
+		.map(|entry_point| entry_point.function_reflection().name().unwrap_or_default().to_owned())
+		.collect();
+
+	let components = std::iter::once(module.into())
+		.chain(entry_points.into_iter().map(ComponentType::from))
+		.collect::<Vec<_>>();
+	let program = session.create_composite_component_type(&components)?;
+	let generated = link_and_generate(&program, components.len() - 1, desc.targets.len(), &mut diagnostics)?;
+
+	let outputs = generated.code.into_iter().enumerate().map(|(target, code)| {
+		let reflection = ProgramReflection::new(generated.linked_program.layout(target as i64)?);
+		Ok(TargetOutput { code, reflection })
+	}).collect::<Result<Vec<_>>>()?;
+
+	Ok(CompiledProgram { entry_points: entry_point_names, targets: outputs, diagnostics, dependencies })
+}
@@ -1,11 +1,63 @@
 //! Rust bindings for the Slang shader language compiler
 
+#[cfg(feature="build")]
+pub mod build;
+pub mod codegen;
+#[cfg(feature="hot_reload")]
+pub mod hot_reload;
 pub mod reflection;
 
+mod command_line;
+pub use command_line::ParsedSessionDesc;
+
+mod builtins;
+pub use builtins::BuiltinBundle;
+
+mod core_module;
+
+mod hash;
+
+mod stats;
+pub use stats::{CompileStats, OperationStats, OperationTimings, measure_operations};
+use stats::Operation;
+
+mod downstream;
+pub use downstream::{DownstreamCompilers, PASS_THROUGHS};
+
+mod session_config;
+pub use session_config::{SessionConfig, TargetConfig};
+
+mod pipeline;
+
+mod preprocessor;
+pub use preprocessor::{CompiledPermutation, MacroPermutations, MacroSet};
+
+mod permutation;
+pub use permutation::{Axis, AxisKind, PermutationKey, PermutationSet, Variant, VariantId};
+
+mod diagnostics;
+pub use diagnostics::{Diagnostic, Diagnostics, Severity, Span};
+
+mod compile_pool;
+pub use compile_pool::{CompileHandle, CompileJob, CompilePool, CompiledShader};
+
+mod shader_cache;
+pub use shader_cache::{CacheKey, CachedCode, ShaderCache};
+
+mod module_graph;
+pub use module_graph::ModuleGraph;
+
+#[cfg(feature="com_impls")]
+mod module_library;
+#[cfg(feature="com_impls")]
+pub use module_library::{LoadedModule, ModuleLibrary, ModuleOrigin};
+
 #[cfg(feature="com_impls")]
 mod com_impls;
 #[cfg(feature="com_impls")]
-pub use com_impls::{ComPtr, VecBlob};
+pub use com_impls::{
+	ComPtr, VecBlob, BoxedFileSystem, MemoryFileSystem, ShaderFileSystem, StaticFileSystem, normalize_path
+};
 
 #[cfg(test)]
 mod tests;
@@ -17,12 +69,13 @@ use std::ptr::{null, null_mut};
 pub(crate) use shader_slang_sys as sys;
 
 pub use sys::{
-	SlangBindingType as BindingType, SlangCompileTarget as CompileTarget,
+	SlangArchiveType as ArchiveType, SlangBindingType as BindingType, SlangCompileTarget as CompileTarget,
 	SlangDebugInfoLevel as DebugInfoLevel, SlangDeclKind as DeclKind,
 	SlangFloatingPointMode as FloatingPointMode, SlangImageFormat as ImageFormat,
 	SlangLayoutRules as LayoutRules, SlangLineDirectiveMode as LineDirectiveMode,
 	SlangMatrixLayoutMode as MatrixLayoutMode, SlangModifierID as ModifierID,
 	SlangOptimizationLevel as OptimizationLevel, SlangParameterCategory as ParameterCategory,
+	SlangPassThrough as PassThrough, SlangPathType as PathType,
 	SlangReflectionGenericArg as GenericArg, SlangReflectionGenericArgType as GenericArgType,
 	SlangResourceAccess as ResourceAccess, SlangResourceShape as ResourceShape,
 	SlangScalarType as ScalarType, SlangSourceLanguage as SourceLanguage, SlangStage as Stage,
@@ -46,16 +99,27 @@ const fn uuid(uuid: u128) -> UUID {
 	}
 }
 
+#[derive(Clone)]
 pub enum Error {
 	Code(sys::SlangResult),
-	Blob(Blob),
+	Diagnostics(Diagnostics),
+}
+
+impl Error {
+	/// The diagnostics reported alongside the failure, if there were any.
+	pub fn diagnostics(&self) -> Option<&Diagnostics> {
+		match self {
+			Error::Code(_) => None,
+			Error::Diagnostics(diagnostics) => Some(diagnostics),
+		}
+	}
 }
 
 impl std::fmt::Debug for Error {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Error::Code(code) => write!(f, "{}", code),
-			Error::Blob(blob) => write!(f, "{}", blob.as_str().unwrap_or_default()),
+			Error::Diagnostics(diagnostics) => write!(f, "{}", diagnostics),
 		}
 	}
 }
@@ -66,25 +130,39 @@ impl std::fmt::Display for Error {
 	}
 }
 
-unsafe impl Send for Error {}
-unsafe impl Sync for Error {}
 impl std::error::Error for Error {}
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+pub(crate) const SLANG_FAIL: sys::SlangResult = 0x80004005u32 as _;
+pub(crate) const SLANG_E_NOT_FOUND: sys::SlangResult = 0x82000005u32 as _;
+pub(crate) const SLANG_E_INVALID_ARG: sys::SlangResult = 0x80070057u32 as _;
+
 pub(crate) fn succeeded(result: sys::SlangResult) -> bool {
 	result >= 0
 }
 
-fn result_from_blob(code: sys::SlangResult, blob: *mut sys::slang_IBlob) -> Result<()> {
-	if code < 0 && !blob.is_null() {
-		Err(Error::Blob(Blob(IUnknown(
-			std::ptr::NonNull::new(blob as *mut _).unwrap(),
-		))))
+/// Converts the result of a call that reports diagnostics, handing the (possibly empty) diagnostics back on success.
+fn result_from_blob(code: sys::SlangResult, blob: *mut sys::slang_IBlob) -> Result<Diagnostics> {
+	let diagnostics = Diagnostics::from_raw(blob);
+
+	if code < 0 && !diagnostics.is_empty() {
+		Err(Error::Diagnostics(diagnostics))
 	} else if code < 0 {
 		Err(Error::Code(code))
 	} else {
-		Ok(())
+		Ok(diagnostics)
+	}
+}
+
+/// Converts the result of a call that returns a string in a blob.
+fn string_from_blob(code: sys::SlangResult, blob: *mut sys::slang_IBlob) -> Result<String> {
+	let blob = std::ptr::NonNull::new(blob as *mut _).map(|blob| Blob(IUnknown(blob)));
+
+	match blob {
+		Some(blob) if succeeded(code) => Ok(String::from_utf8_lossy(blob.as_slice()).into_owned()),
+		_ if succeeded(code) => Err(Error::Code(SLANG_FAIL)),
+		_ => Err(Error::Code(code)),
 	}
 }
 
@@ -134,6 +212,32 @@ pub unsafe trait Interface: Sized + Clone {
 	}
 }
 
+/// Moves a *Slang* object to another thread.
+///
+/// None of the *Slang* wrappers implement [`Send`]: *Slang* reference counts its objects non-atomically, and every
+/// object created from a [`GlobalSession`] shares state with it. Moving an object to another thread is only sound if
+/// no other reference into the same global session is used while it is away, e.g. when a freshly created
+/// `GlobalSession` is handed to a worker thread as a whole. Prefer [`CompilePool`], which keeps all *Slang* objects
+/// on their worker threads and only hands out owned data.
+pub struct Sendable<T: Interface>(T);
+
+// SAFETY: Upheld by the caller of `Sendable::new`.
+unsafe impl<T: Interface> Send for Sendable<T> {}
+
+impl<T: Interface> Sendable<T> {
+	/// # Safety
+	///
+	/// Until the wrapped object is unwrapped again, no other object created from the same [`GlobalSession`]
+	/// (including the session itself) may be used, cloned or dropped on any other thread.
+	pub unsafe fn new(value: T) -> Self {
+		Self(value)
+	}
+
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
 #[repr(transparent)]
 pub struct IUnknown(std::ptr::NonNull<std::ffi::c_void>);
 
@@ -229,6 +333,71 @@ impl GlobalSession {
 		let tag = vcall!(self, getBuildTagString());
 		unsafe { CStr::from_ptr(tag).to_str().unwrap() }
 	}
+
+	/// Sets the code prepended to everything *Slang* generates in `language`, e.g. the definitions that
+	/// [`CompileTarget::CppSource`] and [`CompileTarget::HostCppSource`] output relies on. To extend the default prelude
+	/// instead of replacing it, append to [`language_prelude`](Self::language_prelude). Fails if `prelude` contains a
+	/// NUL character.
+	pub fn set_language_prelude(&self, language: SourceLanguage, prelude: &str) -> Result<()> {
+		let prelude = CString::new(prelude).map_err(|_| Error::Code(SLANG_E_INVALID_ARG))?;
+		vcall!(self, setLanguagePrelude(language, prelude.as_ptr()));
+		Ok(())
+	}
+
+	/// The prelude for `language`, empty if there is none.
+	pub fn language_prelude(&self, language: SourceLanguage) -> String {
+		let mut prelude = null_mut();
+		vcall!(self, getLanguagePrelude(language, &mut prelude));
+		let prelude = std::ptr::NonNull::new(prelude as *mut _).map(|prelude| Blob(IUnknown(prelude)));
+		prelude.map(|prelude| String::from_utf8_lossy(prelude.as_slice()).into_owned()).unwrap_or_default()
+	}
+
+	/// A digest of everything in a session description that affects compiled code, for use as part of a cache key.
+	pub fn session_desc_digest(&self, desc: &SessionDesc) -> Result<Blob> {
+		let mut digest = null_mut();
+		let result = vcall!(self, getSessionDescDigest(&**desc, &mut digest));
+		if !succeeded(result) {
+			return Err(Error::Code(result));
+		}
+		Ok(Blob(IUnknown(std::ptr::NonNull::new(digest as *mut _).ok_or(Error::Code(SLANG_FAIL))?)))
+	}
+}
+
+/// An argument for one specialization parameter of a [`ComponentType`], i.e. a global `type_param` or a generic
+/// parameter of an entry point.
+#[derive(Clone, Copy)]
+pub enum SpecializationArg<'a> {
+	/// A concrete type, usually obtained through [`reflection::Shader::find_type_by_name`].
+	Type(&'a reflection::Type),
+	/// A *Slang* expression, e.g. `"4"` for a generic value parameter or the name of a type.
+	Expr(&'a str),
+}
+
+/// Lays out a list of [`SpecializationArg`]s the way *Slang* expects them. The returned strings back the expression
+/// arguments and must outlive any use of the raw arguments. Fails if an expression contains a NUL character.
+fn raw_specialization_args(args: &[SpecializationArg]) -> Result<(Vec<sys::slang_SpecializationArg>, Vec<CString>)> {
+	let exprs = args.iter().filter_map(|arg| match arg {
+		SpecializationArg::Expr(expr) => Some(CString::new(*expr).map_err(|_| Error::Code(SLANG_E_INVALID_ARG))),
+		SpecializationArg::Type(_) => None,
+	}).collect::<Result<Vec<_>>>()?;
+
+	let mut next_expr = exprs.iter();
+	let raw = args.iter().map(|arg| match arg {
+		SpecializationArg::Type(ty) => sys::slang_SpecializationArg {
+			kind: sys::slang_SpecializationArg_Kind::Type,
+			__bindgen_anon_1: sys::slang_SpecializationArg__bindgen_ty_1 {
+				type_: *ty as *const _ as *mut _,
+			},
+		},
+		SpecializationArg::Expr(_) => sys::slang_SpecializationArg {
+			kind: sys::slang_SpecializationArg_Kind::Expr,
+			__bindgen_anon_1: sys::slang_SpecializationArg__bindgen_ty_1 {
+				expr: next_expr.next().unwrap().as_ptr(),
+			},
+		},
+	}).collect();
+
+	Ok((raw, exprs))
 }
 
 #[repr(transparent)]
@@ -240,23 +409,35 @@ unsafe impl Interface for Session {
 	const IID: UUID = uuid(0x67618701_d116_468f_ab3b474bedce0e3d);
 }
 
+/// Wraps a module returned by one of the `loadModule*` calls, which is owned by the session and therefore needs its
+/// reference count bumped.
+fn module_from_raw(module: *mut sys::slang_IModule, diagnostics: *mut sys::slang_IBlob) -> Result<(Module, Diagnostics)> {
+	let diagnostics = Diagnostics::from_raw(diagnostics);
+
+	match std::ptr::NonNull::new(module as *mut _) {
+		None if diagnostics.is_empty() => Err(Error::Code(SLANG_FAIL)),
+		None => Err(Error::Diagnostics(diagnostics)),
+		Some(module) => {
+			let module = Module(IUnknown(module));
+			unsafe { (module.as_unknown().vtable().ISlangUnknown_addRef)(module.as_raw()) };
+			Ok((module, diagnostics))
+		}
+	}
+}
+
 impl Session {
 	pub fn load_module(&self, name: &str) -> Result<Module> {
+		self.load_module_with_diagnostics(name).map(|(module, _)| module)
+	}
+
+	/// Like [`load_module`](Self::load_module), but also returns the warnings reported while loading the module.
+	pub fn load_module_with_diagnostics(&self, name: &str) -> Result<(Module, Diagnostics)> {
 		let name = CString::new(name).unwrap();
 		let mut diagnostics = null_mut();
 
-		let module = vcall!(self, loadModule(name.as_ptr(), &mut diagnostics));
+		let module = stats::timed(Operation::LoadModule, || vcall!(self, loadModule(name.as_ptr(), &mut diagnostics)));
 
-		if module.is_null() {
-			let blob = Blob(IUnknown(
-				std::ptr::NonNull::new(diagnostics as *mut _).unwrap(),
-			));
-			Err(Error::Blob(blob))
-		} else {
-			let module = Module(IUnknown(std::ptr::NonNull::new(module as *mut _).unwrap()));
-			unsafe { (module.as_unknown().vtable().ISlangUnknown_addRef)(module.as_raw()) };
-			Ok(module)
-		}
+		module_from_raw(module, diagnostics)
 	}
 
 	pub fn load_module_from_source_string(
@@ -265,12 +446,21 @@ impl Session {
 		path: &str,
 		source: &str,
 	) -> Result<Module> {
+		self.load_module_from_source_string_with_diagnostics(module_name, path, source).map(|(module, _)| module)
+	}
+
+	pub fn load_module_from_source_string_with_diagnostics(
+		&self,
+		module_name: &str,
+		path: &str,
+		source: &str,
+	) -> Result<(Module, Diagnostics)> {
 		let module_name = CString::new(module_name).unwrap();
 		let path = CString::new(path).unwrap();
 		let source = CString::new(source).unwrap();
 		let mut diagnostics = null_mut();
 
-		let module = vcall!(
+		let module = stats::timed(Operation::LoadModule, || vcall!(
 			self,
 			loadModuleFromSourceString(
 				module_name.as_ptr(),
@@ -278,18 +468,9 @@ impl Session {
 				source.as_ptr(),
 				&mut diagnostics
 			)
-		);
+		));
 
-		if module.is_null() {
-			let blob = Blob(IUnknown(
-				std::ptr::NonNull::new(diagnostics as *mut _).unwrap(),
-			));
-			Err(Error::Blob(blob))
-		} else {
-			let module = Module(IUnknown(std::ptr::NonNull::new(module as *mut _).unwrap()));
-			unsafe { (module.as_unknown().vtable().ISlangUnknown_addRef)(module.as_raw()) };
-			Ok(module)
-		}
+		module_from_raw(module, diagnostics)
 	}
 
 	#[cfg(feature="com_impls")]
@@ -300,7 +481,7 @@ impl Session {
 		path: &str,
 		ir_blob: &impl com_impls::ImplementsISlangBlob,
 	) -> Result<Module> {
-		self.load_module_from_ir_blob_impl(module_name, path, ir_blob)
+		self.load_module_from_ir_blob_impl(module_name, path, ir_blob).map(|(module, _)| module)
 	}
 
 	#[cfg(not(feature="com_impls"))]
@@ -311,7 +492,7 @@ impl Session {
 		path: &str,
 		ir_blob: &Blob,
 	) -> Result<Module> {
-		self.load_module_from_ir_blob_impl(module_name, path, ir_blob)
+		self.load_module_from_ir_blob_impl(module_name, path, ir_blob).map(|(module, _)| module)
 	}
 
 	fn load_module_from_ir_blob_impl(
@@ -319,12 +500,12 @@ impl Session {
 		module_name: &str,
 		path: &str,
 		ir_blob: &impl Interface,
-	) -> Result<Module> {
+	) -> Result<(Module, Diagnostics)> {
 		let module_name = CString::new(module_name).unwrap();
 		let path = CString::new(path).unwrap();
 		let mut diagnostics = null_mut();
 
-		let module = vcall!(
+		let module = stats::timed(Operation::LoadModule, || vcall!(
 			self,
 			loadModuleFromIRBlob(
 				module_name.as_ptr(),
@@ -332,20 +513,147 @@ impl Session {
 				ir_blob.as_raw(),
 				&mut diagnostics
 			)
+		));
+
+		module_from_raw(module, diagnostics)
+	}
+
+	pub fn loaded_module_count(&self) -> u32 {
+		vcall!(self, getLoadedModuleCount()) as _
+	}
+
+	pub fn loaded_module(&self, index: u32) -> Option<Module> {
+		let module = vcall!(self, getLoadedModule(index as _));
+		// The session keeps ownership of the module, so the returned pointer doesn't carry a reference for us
+		let module = std::mem::ManuallyDrop::new(Module(IUnknown(std::ptr::NonNull::new(module as *mut _)?)));
+		Some((*module).clone())
+	}
+
+	/// All modules loaded into this session, including those only pulled in through `import`.
+	pub fn loaded_modules(&self) -> impl ExactSizeIterator<Item = Module> {
+		(0..self.loaded_module_count()).map(|i| self.loaded_module(i).unwrap())
+	}
+
+	/// The import relations between all [loaded modules](Self::loaded_modules).
+	pub fn module_graph(&self) -> ModuleGraph {
+		ModuleGraph::new(self.loaded_modules().collect())
+	}
+
+	/// Checks whether a serialized module was compiled from the current contents of the source file at `path` and
+	/// all files it depends on, with the options of this session.
+	#[cfg(feature="com_impls")]
+	#[inline(always)]
+	pub fn is_binary_module_up_to_date(&self, path: &str, binary_module: &impl com_impls::ImplementsISlangBlob) -> bool {
+		self.is_binary_module_up_to_date_impl(path, binary_module)
+	}
+
+	/// Checks whether a serialized module was compiled from the current contents of the source file at `path` and
+	/// all files it depends on, with the options of this session.
+	#[cfg(not(feature="com_impls"))]
+	#[inline(always)]
+	pub fn is_binary_module_up_to_date(&self, path: &str, binary_module: &Blob) -> bool {
+		self.is_binary_module_up_to_date_impl(path, binary_module)
+	}
+
+	fn is_binary_module_up_to_date_impl(&self, path: &str, binary_module: &impl Interface) -> bool {
+		let path = CString::new(path).unwrap();
+		vcall!(self, isBinaryModuleUpToDate(path.as_ptr(), binary_module.as_raw()))
+	}
+
+	/// Specializes a generic type, e.g. `Material<T>`, for the given arguments. The result is owned by the session.
+	pub fn specialize_type(&self, ty: &reflection::Type, args: &[SpecializationArg]) -> Result<&reflection::Type> {
+		let (args, _exprs) = raw_specialization_args(args)?;
+		let mut diagnostics = null_mut();
+
+		let specialized = vcall!(
+			self,
+			specializeType(ty as *const _ as *mut _, args.as_ptr(), args.len() as _, &mut diagnostics)
 		);
+		let diagnostics = Diagnostics::from_raw(diagnostics);
 
-		if module.is_null() {
-			let blob = Blob(IUnknown(
-				std::ptr::NonNull::new(diagnostics as *mut _).unwrap(),
-			));
-			Err(Error::Blob(blob))
+		if specialized.is_null() && diagnostics.is_empty() {
+			Err(Error::Code(SLANG_FAIL))
+		} else if specialized.is_null() {
+			Err(Error::Diagnostics(diagnostics))
 		} else {
-			let module = Module(IUnknown(std::ptr::NonNull::new(module as *mut _).unwrap()));
-			unsafe { (module.as_unknown().vtable().ISlangUnknown_addRef)(module.as_raw()) };
-			Ok(module)
+			Ok(unsafe { &*(specialized as *const _) })
 		}
 	}
 
+	/// Creates a component that makes `ty` available as an implementation of `interface` for dynamic dispatch. Pass
+	/// `None` as `id_override` to let *Slang* assign the conformance ID.
+	pub fn create_type_conformance(
+		&self,
+		ty: &reflection::Type,
+		interface: &reflection::Type,
+		id_override: Option<i64>,
+	) -> Result<TypeConformance> {
+		let mut conformance = null_mut();
+		let mut diagnostics = null_mut();
+
+		result_from_blob(
+			vcall!(
+				self,
+				createTypeConformanceComponentType(
+					ty as *const _ as *mut _,
+					interface as *const _ as *mut _,
+					&mut conformance,
+					id_override.unwrap_or(-1),
+					&mut diagnostics
+				)
+			),
+			diagnostics,
+		)?;
+
+		Ok(TypeConformance(IUnknown(
+			std::ptr::NonNull::new(conformance as *mut _).unwrap(),
+		)))
+	}
+
+	/// The sequential ID of the witness table for `ty` conforming to `interface`, as used by dynamic dispatch code.
+	pub fn type_conformance_witness_sequential_id(
+		&self,
+		ty: &reflection::Type,
+		interface: &reflection::Type,
+	) -> Result<u32> {
+		let mut id = 0;
+		let result = vcall!(
+			self,
+			getTypeConformanceWitnessSequentialID(
+				ty as *const _ as *mut _,
+				interface as *const _ as *mut _,
+				&mut id
+			)
+		);
+
+		if succeeded(result) { Ok(id) } else { Err(Error::Code(result)) }
+	}
+
+	pub fn type_conformance_witness_mangled_name(
+		&self,
+		ty: &reflection::Type,
+		interface: &reflection::Type,
+	) -> Result<String> {
+		let mut name = null_mut();
+		let result = vcall!(
+			self,
+			getTypeConformanceWitnessMangledName(
+				ty as *const _ as *mut _,
+				interface as *const _ as *mut _,
+				&mut name
+			)
+		);
+
+		string_from_blob(result, name)
+	}
+
+	pub fn type_rtti_mangled_name(&self, ty: &reflection::Type) -> Result<String> {
+		let mut name = null_mut();
+		let result = vcall!(self, getTypeRTTIMangledName(ty as *const _ as *mut _, &mut name));
+
+		string_from_blob(result, name)
+	}
+
 	pub fn create_composite_component_type(
 		&self,
 		components: &[ComponentType],
@@ -411,57 +719,154 @@ impl ComponentType {
 		let mut diagnostics = null_mut();
 		let ptr = vcall!(self, getLayout(target, &mut diagnostics));
 
-		if ptr.is_null() {
-			Err(Error::Blob(Blob(IUnknown(
-				std::ptr::NonNull::new(diagnostics as *mut _).unwrap(),
-			))))
+		let diagnostics = Diagnostics::from_raw(diagnostics);
+
+		if ptr.is_null() && diagnostics.is_empty() {
+			Err(Error::Code(SLANG_FAIL))
+		} else if ptr.is_null() {
+			Err(Error::Diagnostics(diagnostics))
 		} else {
 			Ok(unsafe { &*(ptr as *const _) })
 		}
 	}
 
+	/// The number of specialization parameters that still need arguments before this component can generate code.
+	pub fn specialization_param_count(&self) -> i64 {
+		vcall!(self, getSpecializationParamCount())
+	}
+
+	/// Specializes this component for the given arguments, which must match the specialization parameters reported
+	/// by [`reflection::Shader::specialization_params`] in number and order.
+	pub fn specialize(&self, args: &[SpecializationArg]) -> Result<ComponentType> {
+		self.specialize_with_diagnostics(args).map(|(specialized, _)| specialized)
+	}
+
+	pub fn specialize_with_diagnostics(&self, args: &[SpecializationArg]) -> Result<(ComponentType, Diagnostics)> {
+		let (args, _exprs) = raw_specialization_args(args)?;
+		let mut specialized = null_mut();
+		let mut diagnostics = null_mut();
+
+		let diagnostics = result_from_blob(
+			vcall!(self, specialize(args.as_ptr(), args.len() as _, &mut specialized, &mut diagnostics)),
+			diagnostics,
+		)?;
+
+		Ok((ComponentType(IUnknown(
+			std::ptr::NonNull::new(specialized as *mut _).unwrap(),
+		)), diagnostics))
+	}
+
 	pub fn link(&self) -> Result<ComponentType> {
+		self.link_with_diagnostics().map(|(linked, _)| linked)
+	}
+
+	/// Like [`link`](Self::link), but also returns the warnings reported while linking.
+	pub fn link_with_diagnostics(&self) -> Result<(ComponentType, Diagnostics)> {
 		let mut linked_component_type = null_mut();
 		let mut diagnostics = null_mut();
 
-		result_from_blob(
-			vcall!(self, link(&mut linked_component_type, &mut diagnostics)),
+		let diagnostics = result_from_blob(
+			stats::timed(Operation::Link, || vcall!(self, link(&mut linked_component_type, &mut diagnostics))),
 			diagnostics,
 		)?;
 
-		Ok(ComponentType(IUnknown(
+		Ok((ComponentType(IUnknown(
 			std::ptr::NonNull::new(linked_component_type as *mut _).unwrap(),
-		)))
+		)), diagnostics))
+	}
+
+	/// Like [`link`](Self::link), but `options` take precedence over the options of the session for the linked
+	/// program and everything generated from it.
+	pub fn link_with_options(&self, options: &CompilerOptions) -> Result<ComponentType> {
+		self.link_with_options_and_diagnostics(options).map(|(linked, _)| linked)
+	}
+
+	/// Like [`link_with_options`](Self::link_with_options), but also returns the warnings reported while linking.
+	pub fn link_with_options_and_diagnostics(&self, options: &CompilerOptions) -> Result<(ComponentType, Diagnostics)> {
+		let mut linked_component_type = null_mut();
+		let mut diagnostics = null_mut();
+
+		let diagnostics = result_from_blob(
+			stats::timed(Operation::Link, || vcall!(self, linkWithOptions(
+				&mut linked_component_type,
+				options.options.len() as _,
+				options.options.as_ptr() as _,
+				&mut diagnostics
+			))),
+			diagnostics,
+		)?;
+
+		Ok((ComponentType(IUnknown(
+			std::ptr::NonNull::new(linked_component_type as *mut _).unwrap(),
+		)), diagnostics))
 	}
 
 	pub fn target_code(&self, target: i64) -> Result<Blob> {
+		self.target_code_with_diagnostics(target).map(|(code, _)| code)
+	}
+
+	/// Like [`target_code`](Self::target_code), but also returns the warnings reported during code generation.
+	pub fn target_code_with_diagnostics(&self, target: i64) -> Result<(Blob, Diagnostics)> {
 		let mut code = null_mut();
 		let mut diagnostics = null_mut();
 
-		result_from_blob(
-			vcall!(self, getTargetCode(target, &mut code, &mut diagnostics)),
+		let diagnostics = result_from_blob(
+			stats::timed(Operation::TargetCode, || vcall!(self, getTargetCode(target, &mut code, &mut diagnostics))),
 			diagnostics,
 		)?;
 
-		Ok(Blob(IUnknown(
+		Ok((Blob(IUnknown(
 			std::ptr::NonNull::new(code as *mut _).unwrap(),
-		)))
+		)), diagnostics))
 	}
 
 	pub fn entry_point_code(&self, index: i64, target: i64) -> Result<Blob> {
+		self.entry_point_code_with_diagnostics(index, target).map(|(code, _)| code)
+	}
+
+	pub fn entry_point_code_with_diagnostics(&self, index: i64, target: i64) -> Result<(Blob, Diagnostics)> {
 		let mut code = null_mut();
 		let mut diagnostics = null_mut();
 
-		result_from_blob(
-			vcall!(
+		let diagnostics = result_from_blob(
+			stats::timed(Operation::TargetCode, || vcall!(
 				self,
 				getEntryPointCode(index, target, &mut code, &mut diagnostics)
-			),
+			)),
 			diagnostics,
 		)?;
 
-		Ok(Blob(IUnknown(
+		Ok((Blob(IUnknown(
 			std::ptr::NonNull::new(code as *mut _).unwrap(),
+		)), diagnostics))
+	}
+
+	/// A hash of the entry point and all code it depends on for a target, for use as part of a cache key.
+	///
+	/// Together with [`GlobalSession::session_desc_digest`] this identifies the code returned by
+	/// [`entry_point_code`](Self::entry_point_code) without generating it.
+	pub fn entry_point_hash(&self, index: i64, target: i64) -> Result<Blob> {
+		let mut hash = null_mut();
+		vcall!(self, getEntryPointHash(index, target, &mut hash));
+		Ok(Blob(IUnknown(std::ptr::NonNull::new(hash as *mut _).ok_or(Error::Code(SLANG_FAIL))?)))
+	}
+
+	/// Compiles an entry point for a host target (e.g. [`CompileTarget::ShaderHostCallable`]) and loads the result
+	/// as a library that can be called directly from Rust.
+	pub fn entry_point_host_callable(&self, entry_point_index: i32, target_index: i32) -> Result<SharedLibrary> {
+		let mut shared_library = null_mut();
+		let mut diagnostics = null_mut();
+
+		result_from_blob(
+			stats::timed(Operation::TargetCode, || vcall!(
+				self,
+				getEntryPointHostCallable(entry_point_index, target_index, &mut shared_library, &mut diagnostics)
+			)),
+			diagnostics,
+		)?;
+
+		Ok(SharedLibrary(IUnknown(
+			std::ptr::NonNull::new(shared_library as *mut _).unwrap(),
 		)))
 	}
 
@@ -509,6 +914,77 @@ impl ComponentType {
 	}
 }
 
+/// The thread group range a host-callable compute entry point processes in a single call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeVaryingInput {
+	pub start_group_id: [u32; 3],
+	/// One past the last thread group to process.
+	pub end_group_id: [u32; 3],
+}
+
+/// The signature *Slang* generates for host-callable compute entry points.
+pub type ComputeFunc = unsafe extern "C" fn(
+	varying_input: *mut ComputeVaryingInput,
+	entry_point_params: *mut std::ffi::c_void,
+	global_params: *mut std::ffi::c_void,
+);
+
+#[repr(transparent)]
+#[derive(Clone)]
+pub struct SharedLibrary(IUnknown);
+
+unsafe impl Interface for SharedLibrary {
+	type Vtable = sys::ISharedLibraryVtable;
+	const IID: UUID = uuid(0x70dbc7c4_dc3b_4a07_ae7e752af6a81555);
+}
+
+impl SharedLibrary {
+	pub fn find_symbol_address_by_name(&self, name: &str) -> Option<std::ptr::NonNull<std::ffi::c_void>> {
+		let name = CString::new(name).unwrap();
+		std::ptr::NonNull::new(vcall!(self, findSymbolAddressByName(name.as_ptr())))
+	}
+
+	/// Looks up an exported function.
+	///
+	/// # Safety
+	///
+	/// `F` must be a function pointer type matching the signature of the exported function, and the returned pointer
+	/// must not be called after this library has been dropped.
+	pub unsafe fn find_func_by_name<F: Copy>(&self, name: &str) -> Option<F> {
+		assert_eq!(std::mem::size_of::<F>(), std::mem::size_of::<*mut std::ffi::c_void>());
+		let address = self.find_symbol_address_by_name(name)?;
+		Some(unsafe { std::mem::transmute_copy(&address) })
+	}
+
+	/// Runs the compute entry point `name` over the thread groups `[0, group_count)`.
+	///
+	/// # Safety
+	///
+	/// `E` and `G` must match the layout *Slang* uses for the uniform entry point parameters and the global shader
+	/// parameters respectively on the host target, and any pointers they contain must be valid for the accesses the
+	/// shader makes.
+	pub unsafe fn dispatch_compute<E, G>(
+		&self,
+		name: &str,
+		group_count: [u32; 3],
+		entry_point_params: &mut E,
+		global_params: &mut G,
+	) -> Result<()> {
+		let func = unsafe { self.find_func_by_name::<ComputeFunc>(name) }.ok_or(Error::Code(SLANG_E_NOT_FOUND))?;
+		let mut varying_input = ComputeVaryingInput { start_group_id: [0; 3], end_group_id: group_count };
+
+		unsafe {
+			func(
+				&mut varying_input,
+				entry_point_params as *mut E as *mut _,
+				global_params as *mut G as *mut _,
+			)
+		};
+		Ok(())
+	}
+}
+
 #[repr(transparent)]
 #[derive(Clone)]
 pub struct EntryPoint(IUnknown);
@@ -682,6 +1158,27 @@ impl<'a> TargetDesc<'a> {
 		self
 	}
 
+	/// A combination of the `SLANG_TARGET_FLAG_*` bits.
+	pub fn flags(mut self, flags: u32) -> Self {
+		self.inner.flags = flags as _;
+		self
+	}
+
+	pub fn floating_point_mode(mut self, mode: FloatingPointMode) -> Self {
+		self.inner.floatingPointMode = mode;
+		self
+	}
+
+	pub fn line_directive_mode(mut self, mode: LineDirectiveMode) -> Self {
+		self.inner.lineDirectiveMode = mode;
+		self
+	}
+
+	pub fn force_glsl_scalar_buffer_layout(mut self, enable: bool) -> Self {
+		self.inner.forceGLSLScalarBufferLayout = enable;
+		self
+	}
+
 	pub fn options(mut self, options: &'a CompilerOptions) -> Self {
 		self.inner.compilerOptionEntries = options.options.as_ptr() as _;
 		self.inner.compilerOptionEntryCount = options.options.len() as _;
@@ -733,6 +1230,28 @@ impl<'a> SessionDesc<'a> {
 		self.inner.compilerOptionEntryCount = options.options.len() as _;
 		self
 	}
+
+	pub fn preprocessor_macros(mut self, macros: &'a [sys::slang_PreprocessorMacroDesc]) -> Self {
+		self.inner.preprocessorMacros = macros.as_ptr();
+		self.inner.preprocessorMacroCount = macros.len() as _;
+		self
+	}
+
+	pub fn macros(self, macros: &'a MacroSet) -> Self {
+		self.preprocessor_macros(macros.descs())
+	}
+
+	pub fn default_matrix_layout_mode(mut self, mode: MatrixLayoutMode) -> Self {
+		self.inner.defaultMatrixLayoutMode = mode;
+		self
+	}
+
+	/// Makes the session load all files through the given file system instead of the OS file system.
+	#[cfg(feature="com_impls")]
+	pub fn file_system(mut self, file_system: &'a ComPtr<BoxedFileSystem>) -> Self {
+		self.inner.fileSystem = file_system.as_raw() as _;
+		self
+	}
 }
 
 macro_rules! option {
@@ -743,6 +1262,13 @@ macro_rules! option {
 		}
 	};
 
+	($name:ident, $func:ident($p_name1:ident: $p_type1:ident, $p_name2:ident: $p_type2:ident)) => {
+		#[inline(always)]
+		pub fn $func(self, $p_name1: $p_type1, $p_name2: $p_type2) -> Self {
+			self.push_ints(CompilerOptionName::$name, $p_name1 as _, $p_name2 as _)
+		}
+	};
+
 	($name:ident, $func:ident($p_name:ident: &str)) => {
 		#[inline(always)]
 		pub fn $func(self, $p_name: &str) -> Self {
@@ -758,61 +1284,250 @@ macro_rules! option {
 	};
 }
 
-#[derive(Default,Clone)]
+/// The value of a compiler option. Which variant an option takes is documented with its [`CompilerOptions`] method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompilerOptionValue {
+	Int(i32, i32),
+	String(String),
+	Strings(String, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompilerOptionEntry {
+	pub name: CompilerOptionName,
+	pub value: CompilerOptionValue,
+}
+
+/// The format of debug information, for [`CompilerOptions::debug_information_format`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DebugInfoFormat {
+	Default,
+	C7,
+	Pdb,
+	Stabs,
+	Coff,
+	Dwarf,
+}
+
+/// How denormal floating point values are treated, for [`CompilerOptions::denormal_mode_fp32`] and its siblings.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FpDenormalMode {
+	Any,
+	Preserve,
+	FlushToZero,
+}
+
+/// How SPIR-V is generated, for [`CompilerOptions::emit_spirv_method`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EmitSpirvMethod {
+	Default,
+	ViaGlsl,
+	Directly,
+}
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LanguageVersion {
+	Legacy = 2018,
+	V2025 = 2025,
+	V2026 = 2026,
+}
+
+/// The built-in file systems *Slang* can read source files through, for [`CompilerOptions::file_system`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FileSystemType {
+	Default,
+	LoadFile,
+	Os,
+}
+
+/// The HLSL register class whose bindings are shifted by [`CompilerOptions::vulkan_bind_shift`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VulkanShiftKind {
+	/// `b` registers.
+	ConstantBuffer,
+	/// `t` registers.
+	ShaderResource,
+	/// `u` registers.
+	UnorderedAccess,
+	/// `s` registers.
+	Sampler,
+}
+
+/// Options for a session, a target or a [link](ComponentType::link_with_options).
+///
+/// Every option of *Slang* that has an effect outside of the command line has a typed method. All others can be set
+/// through [`raw`](Self::raw).
+#[derive(Default)]
 pub struct CompilerOptions {
 	strings: Vec<CString>,
 	options: Vec<sys::slang_CompilerOptionEntry>,
+	entries: Vec<CompilerOptionEntry>,
+}
+
+// SAFETY: The only pointers held by the option entries point into `strings`, which is owned and never mutated once
+// pushed, so the options can be moved to and read from other threads.
+unsafe impl Send for CompilerOptions {}
+unsafe impl Sync for CompilerOptions {}
+
+impl Clone for CompilerOptions {
+	fn clone(&self) -> Self {
+		// The raw entries point into `strings` in the order they were pushed, so they are pointed at the clone's own
+		// strings in the same order
+		let strings = self.strings.clone();
+		let mut clone_strings = strings.iter().map(|s| s.as_ptr());
+		let mut clone_string = |ptr: *const std::ffi::c_char| match ptr.is_null() {
+			true => ptr,
+			false => clone_strings.next().unwrap_or(null()),
+		};
+
+		let options = self.options.iter().map(|option| {
+			let mut option = *option;
+			option.value.stringValue0 = clone_string(option.value.stringValue0);
+			option.value.stringValue1 = clone_string(option.value.stringValue1);
+			option
+		}).collect();
+
+		Self { strings, options, entries: self.entries.clone() }
+	}
+}
+
+impl std::fmt::Debug for CompilerOptions {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_list().entries(&self.entries).finish()
+	}
+}
+
+impl PartialEq for CompilerOptions {
+	fn eq(&self, other: &Self) -> bool {
+		self.entries == other.entries
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CompilerOptions {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		self.entries.serialize(serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CompilerOptions {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+		let entries = Vec::<CompilerOptionEntry>::deserialize(deserializer)?;
+		Self::from_entries(entries).map_err(|_| serde::de::Error::custom("invalid compiler option value"))
+	}
 }
 
 impl CompilerOptions {
-	fn push_ints(mut self, name: CompilerOptionName, i0: i32, i1: i32) -> Self {
-		self.options.push(sys::slang_CompilerOptionEntry {
-			name,
-			value: sys::slang_CompilerOptionValue {
+	/// Builds options from entries, failing like [`raw`](Self::raw) for invalid ones.
+	pub fn from_entries(entries: impl IntoIterator<Item = CompilerOptionEntry>) -> Result<Self> {
+		entries.into_iter().try_fold(Self::default(), |options, entry| options.raw(entry.name, entry.value))
+	}
+
+	/// All options in the order they were added.
+	pub fn entries(&self) -> &[CompilerOptionEntry] {
+		&self.entries
+	}
+
+	/// Adds an option without a typed method.
+	///
+	/// Fails if the value has the wrong kind for an option, e.g. an integer for [`CompilerOptionName::Include`], or if a
+	/// string contains a NUL character.
+	pub fn raw(self, name: CompilerOptionName, value: CompilerOptionValue) -> Result<Self> {
+		let expected_kind = match name {
+			CompilerOptionName::CountOfParsableOptions | CompilerOptionName::CountOf
+			| CompilerOptionName::REMOVED_SerialIR => return Err(Error::Code(SLANG_E_INVALID_ARG)),
+			name => option_value_kind(name),
+		};
+		let (kind, has_nul) = match &value {
+			CompilerOptionValue::Int(..) => (sys::slang_CompilerOptionValueKind::Int, false),
+			CompilerOptionValue::String(s0) => (sys::slang_CompilerOptionValueKind::String, s0.contains('\0')),
+			CompilerOptionValue::Strings(s0, s1) => {
+				(sys::slang_CompilerOptionValueKind::String, s0.contains('\0') || s1.contains('\0'))
+			}
+		};
+		if kind != expected_kind || has_nul {
+			return Err(Error::Code(SLANG_E_INVALID_ARG));
+		}
+
+		Ok(self.push(name, value))
+	}
+
+	fn push(mut self, name: CompilerOptionName, value: CompilerOptionValue) -> Self {
+		let mut push_string = |s: &str| {
+			let s = CString::new(s).unwrap();
+			let ptr = s.as_ptr();
+			self.strings.push(s);
+			ptr
+		};
+
+		let raw_value = match &value {
+			CompilerOptionValue::Int(i0, i1) => sys::slang_CompilerOptionValue {
 				kind: sys::slang_CompilerOptionValueKind::Int,
-				intValue0: i0,
-				intValue1: i1,
+				intValue0: *i0,
+				intValue1: *i1,
 				stringValue0: null(),
 				stringValue1: null(),
 			},
-		});
-
-		self
-	}
-
-	fn push_strings(mut self, name: CompilerOptionName, s0: *const i8, s1: *const i8) -> Self {
-		self.options.push(sys::slang_CompilerOptionEntry {
-			name,
-			value: sys::slang_CompilerOptionValue {
+			CompilerOptionValue::String(s0) => sys::slang_CompilerOptionValue {
 				kind: sys::slang_CompilerOptionValueKind::String,
 				intValue0: 0,
 				intValue1: 0,
-				stringValue0: s0,
-				stringValue1: s1,
+				stringValue0: push_string(s0),
+				stringValue1: null(),
 			},
-		});
+			CompilerOptionValue::Strings(s0, s1) => sys::slang_CompilerOptionValue {
+				kind: sys::slang_CompilerOptionValueKind::String,
+				intValue0: 0,
+				intValue1: 0,
+				stringValue0: push_string(s0),
+				stringValue1: push_string(s1),
+			},
+		};
 
+		self.options.push(sys::slang_CompilerOptionEntry { name, value: raw_value });
+		self.entries.push(CompilerOptionEntry { name, value });
 		self
 	}
 
-	fn push_str1(mut self, name: CompilerOptionName, s0: &str) -> Self {
-		let s0 = CString::new(s0).unwrap();
-		let s0_ptr = s0.as_ptr();
-		self.strings.push(s0);
+	fn push_ints(self, name: CompilerOptionName, i0: i32, i1: i32) -> Self {
+		self.push(name, CompilerOptionValue::Int(i0, i1))
+	}
 
-		self.push_strings(name, s0_ptr, null())
+	fn push_str1(self, name: CompilerOptionName, s0: &str) -> Self {
+		self.push(name, CompilerOptionValue::String(s0.to_owned()))
 	}
 
-	fn push_str2(mut self, name: CompilerOptionName, s0: &str, s1: &str) -> Self {
-		let s0 = CString::new(s0).unwrap();
-		let s0_ptr = s0.as_ptr();
-		self.strings.push(s0);
+	fn push_str2(self, name: CompilerOptionName, s0: &str, s1: &str) -> Self {
+		self.push(name, CompilerOptionValue::Strings(s0.to_owned(), s1.to_owned()))
+	}
+}
 
-		let s1 = CString::new(s1).unwrap();
-		let s1_ptr = s1.as_ptr();
-		self.strings.push(s1);
+/// Whether an option takes integers or strings.
+fn option_value_kind(name: CompilerOptionName) -> sys::slang_CompilerOptionValueKind {
+	use CompilerOptionName::*;
 
-		self.push_strings(name, s0_ptr, s1_ptr)
+	match name {
+		MacroDefine | DepFile | EntryPointName | Specialize | Include | ModuleName | Output | WarningsAsErrors
+		| DisableWarnings | EnableWarning | DisableWarning | SourceEmbedName | SPIRVCoreGrammarJSON | DownstreamArgs
+		| DumpRepro | ExtractRepro | LoadRepro | LoadReproDirectory | ReproFallbackDirectory | DumpIntermediatePrefix
+		| ReproFileSystem | LoadCoreModule | ReferenceModule | SaveCoreModule | SaveCoreModuleBinSource
+		| TypeConformance | SaveGLSLModuleBinSource | DumpModule => sys::slang_CompilerOptionValueKind::String,
+		_ => sys::slang_CompilerOptionValueKind::Int,
 	}
 }
 
@@ -822,6 +1537,10 @@ impl CompilerOptions {
 	option!(Language, language(language: SourceLanguage));
 	option!(MatrixLayoutColumn, matrix_layout_column(enable: bool));
 	option!(MatrixLayoutRow, matrix_layout_row(enable: bool));
+	option!(ZeroInitialize, zero_initialize(enable: bool));
+	option!(IgnoreCapabilities, ignore_capabilities(enable: bool));
+	option!(RestrictiveCapabilityCheck, restrictive_capability_check(enable: bool));
+	option!(ModuleName, module_name(name: &str));
 
 	#[inline(always)]
 	pub fn profile(self, profile: ProfileID) -> Self {
@@ -834,9 +1553,18 @@ impl CompilerOptions {
 	option!(DisableWarnings, disable_warnings(warning_codes: &str));
 	option!(EnableWarning, enable_warning(warning_code: &str));
 	option!(DisableWarning, disable_warning(warning_code: &str));
+	option!(DumpWarningDiagnostics, dump_warning_diagnostics(enable: bool));
+	option!(EmitIr, emit_ir(enable: bool));
 	option!(ReportDownstreamTime, report_downstream_time(enable: bool));
 	option!(ReportPerfBenchmark, report_perf_benchmark(enable: bool));
+	option!(ReportCheckpointIntermediates, report_checkpoint_intermediates(enable: bool));
 	option!(SkipSPIRVValidation, skip_spirv_validation(enable: bool));
+	option!(DisableShortCircuit, disable_short_circuit(enable: bool));
+	option!(MinimumSlangOptimization, minimum_slang_optimization(enable: bool));
+	option!(DisableNonEssentialValidations, disable_non_essential_validations(enable: bool));
+	option!(DisableSourceMap, disable_source_map(enable: bool));
+	option!(UnscopedEnum, unscoped_enum(enable: bool));
+	option!(PreserveParameters, preserve_parameters(enable: bool));
 
 	// Target
 	#[inline(always)]
@@ -852,14 +1580,103 @@ impl CompilerOptions {
 	option!(LineDirectiveMode, line_directive_mode(mode: LineDirectiveMode));
 	option!(Optimization, optimization(level: OptimizationLevel));
 	option!(Obfuscate, obfuscate(enable: bool));
+
+	/// Shifts the bindings of HLSL registers of `kind` in register space `set` by `shift`, like `-fvk-b-shift` and
+	/// its siblings.
+	#[inline(always)]
+	pub fn vulkan_bind_shift(self, kind: VulkanShiftKind, set: u32, shift: u32) -> Self {
+		self.push_ints(CompilerOptionName::VulkanBindShift, ((kind as i32) << 24) | (set as i32 & 0xffffff), shift as _)
+	}
+
+	/// Places the global constant buffer at binding `index` of descriptor set `set`.
+	#[inline(always)]
+	pub fn vulkan_bind_globals(self, index: u32, set: u32) -> Self {
+		self.push_ints(CompilerOptionName::VulkanBindGlobals, index as _, set as _)
+	}
+
+	option!(VulkanInvertY, vulkan_invert_y(enable: bool));
+	option!(VulkanUseDxPositionW, vulkan_use_dx_position_w(enable: bool));
 	option!(VulkanUseEntryPointName, vulkan_use_entry_point_name(enable: bool));
+	option!(VulkanUseGLLayout, vulkan_use_gl_layout(enable: bool));
+	option!(VulkanEmitReflection, vulkan_emit_reflection(enable: bool));
 	option!(GLSLForceScalarLayout, glsl_force_scalar_layout(enable: bool));
+	option!(EnableEffectAnnotations, enable_effect_annotations(enable: bool));
+	option!(EmitSpirvViaGLSL, emit_spirv_via_glsl(enable: bool));
 	option!(EmitSpirvDirectly, emit_spirv_directly(enable: bool));
+	option!(SPIRVCoreGrammarJSON, spirv_core_grammar_json(path: &str));
+	option!(IncompleteLibrary, incomplete_library(enable: bool));
+
+	// Downstream
+	option!(DefaultDownstreamCompiler, default_downstream_compiler(language: SourceLanguage, compiler: PassThrough));
+
+	/// Passes additional arguments, one per line, to a downstream compiler such as `dxc` or `glslang`.
+	#[inline(always)]
+	pub fn downstream_args(self, compiler: &str, args: &str) -> Self {
+		self.push_str2(CompilerOptionName::DownstreamArgs, compiler, args)
+	}
+
+	option!(PassThrough, pass_through(compiler: PassThrough));
+
+	// Repro
+	option!(DumpRepro, dump_repro(path: &str));
+	option!(DumpReproOnError, dump_repro_on_error(enable: bool));
+	option!(ReproFallbackDirectory, repro_fallback_directory(path: &str));
 
 	// Debugging
+	option!(DumpAst, dump_ast(enable: bool));
+	option!(DumpIntermediatePrefix, dump_intermediate_prefix(prefix: &str));
+	option!(DumpIntermediates, dump_intermediates(enable: bool));
+	option!(DumpIr, dump_ir(enable: bool));
+	option!(DumpIrIds, dump_ir_ids(enable: bool));
+	option!(PreprocessorOutput, preprocessor_output(enable: bool));
+	option!(OutputIncludes, output_includes(enable: bool));
+	option!(SkipCodeGen, skip_code_gen(enable: bool));
+	option!(ValidateIr, validate_ir(enable: bool));
+	option!(VerbosePaths, verbose_paths(enable: bool));
+	option!(VerifyDebugSerialIr, verify_debug_serial_ir(enable: bool));
 	option!(NoCodeGen, no_code_gen(enable: bool));
 
+	// Internal
+	option!(FileSystem, file_system(file_system: FileSystemType));
+	option!(Heterogeneous, heterogeneous(enable: bool));
+	option!(NoHLSLBinding, no_hlsl_binding(enable: bool));
+	option!(NoHLSLPackConstantBufferElements, no_hlsl_pack_constant_buffer_elements(enable: bool));
+	option!(AllowGLSL, allow_glsl(enable: bool));
+	option!(BindlessSpaceIndex, bindless_space_index(index: i32));
+
+	// Modules
+	option!(ArchiveType, archive_type(archive_type: ArchiveType));
+	option!(ReferenceModule, reference_module(path: &str));
+	option!(UseUpToDateBinaryModule, use_up_to_date_binary_module(enable: bool));
+	option!(GenerateWholeProgram, generate_whole_program(enable: bool));
+	option!(EmbedDownstreamIR, embed_downstream_ir(enable: bool));
+	option!(SkipDownstreamLinking, skip_downstream_linking(enable: bool));
+
+	// Code generation
+	option!(TrackLiveness, track_liveness(enable: bool));
+	option!(LoopInversion, loop_inversion(enable: bool));
+	option!(ParameterBlocksUseRegisterSpaces, parameter_blocks_use_register_spaces(enable: bool));
+	option!(LanguageVersion, language_version(version: LanguageVersion));
+
+	/// Links an additional type conformance, in the form `Type:IInterface[=id]`.
+	#[inline(always)]
+	pub fn type_conformance(self, conformance: &str) -> Self {
+		self.push_str1(CompilerOptionName::TypeConformance, conformance)
+	}
+
+	option!(EmitReflectionJSON, emit_reflection_json(enable: bool));
+	option!(DebugInformationFormat, debug_information_format(format: DebugInfoFormat));
+	option!(VulkanBindShiftAll, vulkan_bind_shift_all(kind: VulkanShiftKind, shift: u32));
+	option!(ForceDXLayout, force_dx_layout(enable: bool));
+	option!(EmitSpirvMethod, emit_spirv_method(method: EmitSpirvMethod));
+	option!(EmitSeparateDebug, emit_separate_debug(enable: bool));
+	option!(DenormalModeFp16, denormal_mode_fp16(mode: FpDenormalMode));
+	option!(DenormalModeFp32, denormal_mode_fp32(mode: FpDenormalMode));
+	option!(DenormalModeFp64, denormal_mode_fp64(mode: FpDenormalMode));
+
 	// Experimental
 	option!(NoMangle, no_mangle(enable: bool));
 	option!(ValidateUniformity, validate_uniformity(enable: bool));
+	option!(EnableExperimentalPasses, enable_experimental_passes(enable: bool));
+	option!(EnableExperimentalDynamicDispatch, enable_experimental_dynamic_dispatch(enable: bool));
 }
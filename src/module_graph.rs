@@ -0,0 +1,74 @@
+use std::collections::BTreeSet;
+
+use crate::Module;
+
+/// The modules loaded into a [`Session`](crate::Session) and which of them import which.
+///
+/// *Slang* doesn't report imports directly, but the [dependency files](Module::dependency_file_paths) of a module
+/// include the files of every module it imports, directly or indirectly. The graph is derived from those, so modules
+/// loaded from a source string are only connected if they were given a path. Which of those imports are direct can't
+/// be told apart, so the graph offers the [transitive reduction](Self::reduced_imports) instead.
+pub struct ModuleGraph {
+	modules: Vec<Module>,
+	dependencies: Vec<BTreeSet<String>>,
+	transitive_imports: Vec<Vec<usize>>,
+	reduced_imports: Vec<Vec<usize>>,
+}
+
+impl ModuleGraph {
+	pub fn new(modules: Vec<Module>) -> Self {
+		let dependencies = modules.iter()
+			.map(|module| module.dependency_file_paths().map(str::to_owned).collect::<BTreeSet<_>>())
+			.collect::<Vec<_>>();
+
+		let transitive_imports = modules.iter().enumerate().map(|(index, _)| {
+			modules.iter().enumerate()
+				.filter(|&(other, module)| other != index && dependencies[index].contains(module.file_path()))
+				.map(|(other, _)| other)
+				.collect::<Vec<_>>()
+		}).collect::<Vec<_>>();
+
+		// An import that is also reachable through another import is only kept as an edge of that other module
+		let reduced_imports = transitive_imports.iter().map(|transitive| {
+			transitive.iter().copied()
+				.filter(|&import| !transitive.iter().any(|&other| other != import && transitive_imports[other].contains(&import)))
+				.collect::<Vec<_>>()
+		}).collect::<Vec<_>>();
+
+		Self { modules, dependencies, transitive_imports, reduced_imports }
+	}
+
+	pub fn modules(&self) -> &[Module] {
+		&self.modules
+	}
+
+	pub fn find_by_name(&self, name: &str) -> Option<usize> {
+		self.modules.iter().position(|module| module.name() == name)
+	}
+
+	/// The modules imported by module `index` that aren't already imported through one of the others, i.e. the
+	/// transitive reduction of the graph.
+	pub fn reduced_imports(&self, index: usize) -> &[usize] {
+		&self.reduced_imports[index]
+	}
+
+	/// All modules imported by module `index`, directly or indirectly.
+	pub fn transitive_imports(&self, index: usize) -> &[usize] {
+		&self.transitive_imports[index]
+	}
+
+	/// The modules whose [reduced imports](Self::reduced_imports) contain module `index`.
+	pub fn importers(&self, index: usize) -> impl Iterator<Item = usize> {
+		(0..self.modules.len()).filter(move |&other| self.reduced_imports[other].contains(&index))
+	}
+
+	/// The modules that have to be reloaded when the file at `path` changes, i.e. all modules depending on it.
+	pub fn dependents_of_file(&self, path: &str) -> impl Iterator<Item = usize> {
+		(0..self.modules.len()).filter(move |&index| self.dependencies[index].contains(path))
+	}
+
+	/// The files of all modules, without duplicates.
+	pub fn dependency_file_paths(&self) -> BTreeSet<&str> {
+		self.dependencies.iter().flatten().map(String::as_str).collect()
+	}
+}
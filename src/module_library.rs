@@ -0,0 +1,97 @@
+//! Loading modules from precompiled `.slang-module` files, falling back to their sources.
+
+use std::path::{Path, PathBuf};
+
+use crate::com_impls::{ComPtr, VecBlob};
+use crate::{Diagnostics, Error, Module, Result, SLANG_E_INVALID_ARG, Session};
+
+/// How a [`ModuleLibrary`] obtained a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleOrigin {
+	/// Loaded from an up to date binary module.
+	Binary,
+	/// Compiled from source because there was no binary module yet.
+	Compiled,
+	/// Compiled from source because the binary module was out of date or could not be loaded.
+	Recompiled,
+}
+
+pub struct LoadedModule {
+	pub module: Module,
+	pub origin: ModuleOrigin,
+	pub diagnostics: Diagnostics,
+	/// Where the binary module is stored.
+	pub binary_path: PathBuf,
+}
+
+/// Loads modules through a session, keeping a binary module for every source file.
+///
+/// A binary module is used as long as *Slang* considers it up to date, i.e. neither the source file, nor any file it
+/// depends on, nor the session options have changed since it was written. Otherwise the module is compiled from
+/// source and the binary module is replaced.
+pub struct ModuleLibrary<'a> {
+	session: &'a Session,
+	binary_dir: Option<PathBuf>,
+}
+
+impl<'a> ModuleLibrary<'a> {
+	/// Stores binary modules next to their source files.
+	pub fn new(session: &'a Session) -> Self {
+		Self { session, binary_dir: None }
+	}
+
+	/// Stores binary modules in `dir` instead of next to their source files. Their names include a hash of the full
+	/// source path, so that source files of the same name in different directories don't share a binary module.
+	pub fn binary_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+		self.binary_dir = Some(dir.into());
+		self
+	}
+
+	/// The path of the binary module for a source file.
+	pub fn binary_path(&self, source_path: &Path) -> PathBuf {
+		let file_name = source_path.with_extension("slang-module");
+		let file_name = file_name.file_name().unwrap_or_default();
+		match &self.binary_dir {
+			Some(dir) => {
+				let full_path = std::path::absolute(source_path).unwrap_or_else(|_| source_path.to_owned());
+				let hash = crate::hash::fnv1a_64([full_path.as_os_str().as_encoded_bytes()]);
+				let stem = source_path.file_stem().unwrap_or_default().to_string_lossy();
+				dir.join(format!("{stem}.{hash:016x}.slang-module"))
+			}
+			None => source_path.with_file_name(file_name),
+		}
+	}
+
+	/// Loads the module for the source file at `source_path`, which has to be valid UTF-8.
+	///
+	/// Failing to write the binary module is not an error, the module is then compiled again on the next load.
+	pub fn load(&self, source_path: impl AsRef<Path>) -> Result<LoadedModule> {
+		let source_path = source_path.as_ref();
+		let path = source_path.to_str().ok_or(Error::Code(SLANG_E_INVALID_ARG))?;
+		let module_name = source_path.file_stem().and_then(|s| s.to_str()).unwrap_or(path);
+		let binary_path = self.binary_path(source_path);
+
+		let origin = match std::fs::read(&binary_path) {
+			Ok(bytes) => {
+				let blob = ComPtr::new(VecBlob::from_vec(bytes));
+				let loaded = match self.session.is_binary_module_up_to_date(path, &blob) {
+					true => self.session.load_module_from_ir_blob_impl(module_name, path, &blob).ok(),
+					false => None,
+				};
+				if let Some((module, diagnostics)) = loaded {
+					return Ok(LoadedModule { module, origin: ModuleOrigin::Binary, diagnostics, binary_path });
+				}
+				ModuleOrigin::Recompiled
+			}
+			Err(_) => ModuleOrigin::Compiled,
+		};
+
+		let (module, diagnostics) = self.session.load_module_with_diagnostics(path)?;
+		if let Some(dir) = binary_path.parent() {
+			let _ = std::fs::create_dir_all(dir);
+		}
+		let _ = module.write_to_file(&binary_path);
+
+		Ok(LoadedModule { module, origin, diagnostics, binary_path })
+	}
+}
@@ -0,0 +1,327 @@
+//! Compiling the variants of a shader on demand.
+//!
+//! ```ignore
+//! let mut set = slang::PermutationSet::new(global_session, config, "lighting", "main")
+//!     .macro_axis("SHADOWS", [0, 1])
+//!     .type_axis("TMaterial", ["Lambert", "GGX"])
+//!     .constant_axis("LIGHT_COUNT", "int", [1, 4, 16]);
+//!
+//! let key = slang::PermutationKey::new().set("SHADOWS", 1).set("TMaterial", "GGX");
+//! let variant = set.variant(&key)?;
+//! upload(variant.id, &variant.code[0]);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::pipeline::link_and_generate;
+use crate::reflection::ProgramReflection;
+use crate::{
+	ComponentType, Diagnostics, Error, GlobalSession, MacroSet, Module, OperationTimings, Result, SLANG_E_INVALID_ARG,
+	SLANG_E_NOT_FOUND, Session, SessionConfig, SpecializationArg,
+};
+
+/// What an axis of a [`PermutationSet`] varies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AxisKind {
+	/// A preprocessor macro. Every combination of macros is compiled in its own session.
+	Macro,
+	/// A specialization parameter, i.e. a global `type_param` or a generic parameter of the entry point, matched by
+	/// name. Values are *Slang* expressions, usually type names.
+	Specialization,
+	/// A link-time constant declared as `extern static const` in the module, with the given *Slang* type.
+	Constant(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Axis {
+	pub name: String,
+	pub kind: AxisKind,
+	/// The values the axis takes. The first one is used for keys that don't set the axis.
+	pub values: Vec<String>,
+}
+
+/// The value of every axis of a [`PermutationSet`], identifying one variant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PermutationKey {
+	values: Vec<(String, String)>,
+}
+
+impl PermutationKey {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the value of an axis, replacing any previous value.
+	pub fn set(mut self, axis: &str, value: impl ToString) -> Self {
+		let value = value.to_string();
+		match self.values.binary_search_by(|(name, _)| name.as_str().cmp(axis)) {
+			Ok(index) => self.values[index].1 = value,
+			Err(index) => self.values.insert(index, (axis.to_owned(), value)),
+		}
+		self
+	}
+
+	pub fn get(&self, axis: &str) -> Option<&str> {
+		self.iter().find(|(name, _)| *name == axis).map(|(_, value)| value)
+	}
+
+	/// The axes and their values, sorted by axis name.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, &str)> {
+		self.values.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+	}
+
+	/// The stable ID of the key. It only depends on the axis names and values, so it doesn't change between runs,
+	/// platforms or when axes are declared in a different order, and can be used to name files or cache entries.
+	pub fn id(&self) -> VariantId {
+		VariantId(crate::hash::fnv1a_64(self.iter().flat_map(|(name, value)| [name.as_bytes(), value.as_bytes()])))
+	}
+}
+
+impl std::fmt::Display for PermutationKey {
+	/// Formats the key as `AXIS=VALUE` pairs separated by spaces.
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (index, (name, value)) in self.iter().enumerate() {
+			let separator = if index == 0 { "" } else { " " };
+			write!(f, "{separator}{name}={value}")?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VariantId(pub u64);
+
+impl std::fmt::Display for VariantId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:016x}", self.0)
+	}
+}
+
+/// One compiled variant of a [`PermutationSet`].
+#[derive(Debug, Clone)]
+pub struct Variant {
+	pub id: VariantId,
+	/// The code of the entry point for every target of the session.
+	pub code: Vec<Vec<u8>>,
+	/// The reflection of the first target.
+	pub reflection: ProgramReflection,
+	/// Warnings reported while compiling.
+	pub diagnostics: Diagnostics,
+	/// The time spent compiling the variant. The module is only loaded for the first variant of each macro combination.
+	pub timings: OperationTimings,
+}
+
+/// The variants of an entry point along a set of axes, compiled lazily and kept once compiled.
+///
+/// Variants sharing their macros share a session, so the module is only parsed once per macro combination.
+pub struct PermutationSet {
+	global_session: GlobalSession,
+	config: SessionConfig,
+	module: String,
+	entry_point: String,
+	axes: Vec<Axis>,
+	sessions: HashMap<VariantId, Result<(Session, Module)>>,
+	variants: HashMap<VariantId, (PermutationKey, Result<Variant>)>,
+	/// Whether an axis was left out for reusing the name of another one.
+	rejected: bool,
+}
+
+impl PermutationSet {
+	/// A set of variants of `entry_point` in `module`, compiled with sessions created from `config`.
+	pub fn new(global_session: GlobalSession, config: SessionConfig, module: &str, entry_point: &str) -> Self {
+		Self {
+			global_session,
+			config,
+			module: module.to_owned(),
+			entry_point: entry_point.to_owned(),
+			axes: Vec::new(),
+			sessions: HashMap::new(),
+			variants: HashMap::new(),
+			rejected: false,
+		}
+	}
+
+	/// An axis with the name of an existing one is left out and makes the set [invalid](Self::is_valid).
+	pub fn axis(mut self, axis: Axis) -> Self {
+		match self.axes.iter().any(|other| other.name == axis.name) {
+			true => self.rejected = true,
+			false => self.axes.push(axis),
+		}
+		self
+	}
+
+	/// Whether no axis was left out by [`axis`](Self::axis). No variant of an invalid set can be compiled.
+	pub fn is_valid(&self) -> bool {
+		!self.rejected
+	}
+
+	pub fn macro_axis<V: ToString>(self, name: &str, values: impl IntoIterator<Item = V>) -> Self {
+		self.axis(Axis { name: name.to_owned(), kind: AxisKind::Macro, values: strings(values) })
+	}
+
+	pub fn type_axis<V: ToString>(self, name: &str, values: impl IntoIterator<Item = V>) -> Self {
+		self.axis(Axis { name: name.to_owned(), kind: AxisKind::Specialization, values: strings(values) })
+	}
+
+	pub fn constant_axis<V: ToString>(self, name: &str, ty: &str, values: impl IntoIterator<Item = V>) -> Self {
+		self.axis(Axis { name: name.to_owned(), kind: AxisKind::Constant(ty.to_owned()), values: strings(values) })
+	}
+
+	pub fn axes(&self) -> &[Axis] {
+		&self.axes
+	}
+
+	/// The number of variants, i.e. the product of the number of values of all axes.
+	pub fn len(&self) -> usize {
+		self.axes.iter().map(|axis| axis.values.len()).product()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The keys of all variants. The first axis varies slowest.
+	pub fn keys(&self) -> impl ExactSizeIterator<Item = PermutationKey> {
+		let radices = self.axes.iter().map(|axis| axis.values.len()).collect::<Vec<_>>();
+		(0..self.len()).map(move |index| {
+			self.axes.iter().zip(mixed_radix_digits(index, &radices))
+				.fold(PermutationKey::new(), |key, (axis, digit)| key.set(&axis.name, &axis.values[digit]))
+		})
+	}
+
+	/// Completes `key` with the default value of every axis it doesn't set.
+	///
+	/// Fails if the key sets an axis that doesn't exist or a value the axis doesn't take, or if the set is invalid.
+	pub fn resolve(&self, key: &PermutationKey) -> Result<PermutationKey> {
+		if self.rejected {
+			return Err(Error::Code(SLANG_E_INVALID_ARG));
+		}
+		for (name, value) in key.iter() {
+			let axis = self.axes.iter().find(|axis| axis.name == name).ok_or(Error::Code(SLANG_E_INVALID_ARG))?;
+			if !axis.values.iter().any(|v| v == value) {
+				return Err(Error::Code(SLANG_E_INVALID_ARG));
+			}
+		}
+
+		self.axes.iter().try_fold(key.clone(), |resolved, axis| match key.get(&axis.name) {
+			Some(_) => Ok(resolved),
+			None => axis.values.first()
+				.map(|value| resolved.set(&axis.name, value))
+				.ok_or(Error::Code(SLANG_E_INVALID_ARG)),
+		})
+	}
+
+	/// The variant for `key`, compiled on first use. Failures are kept as well and reported by
+	/// [`failures`](Self::failures).
+	pub fn variant(&mut self, key: &PermutationKey) -> Result<&Variant> {
+		let key = self.resolve(key)?;
+		let id = key.id();
+
+		if !self.variants.contains_key(&id) {
+			let (result, timings) = crate::measure_operations(|| self.compile(&key, id));
+			let result = result.map(|variant| Variant { timings, ..variant });
+			self.variants.insert(id, (key, result));
+		}
+
+		self.variants[&id].1.as_ref().map_err(Error::clone)
+	}
+
+	/// A variant that was already compiled, without compiling it otherwise.
+	pub fn compiled(&self, id: VariantId) -> Option<&Variant> {
+		self.variants.get(&id).and_then(|(_, result)| result.as_ref().ok())
+	}
+
+	/// Compiles every variant that wasn't compiled yet and returns the ones that failed.
+	pub fn compile_all(&mut self) -> impl Iterator<Item = (&PermutationKey, &Error)> {
+		for key in self.keys().collect::<Vec<_>>() {
+			let _ = self.variant(&key);
+		}
+		self.failures()
+	}
+
+	/// The variants that failed to compile so far, along with the reason.
+	pub fn failures(&self) -> impl Iterator<Item = (&PermutationKey, &Error)> {
+		self.variants.values().filter_map(|(key, result)| result.as_ref().err().map(|error| (key, error)))
+	}
+
+	fn compile(&mut self, key: &PermutationKey, id: VariantId) -> Result<Variant> {
+		let axes_of = |kind: fn(&AxisKind) -> bool| self.axes.iter()
+			.filter(move |axis| kind(&axis.kind))
+			.map(move |axis| (axis, key.get(&axis.name).unwrap()));
+
+		let macros = axes_of(|kind| *kind == AxisKind::Macro)
+			.fold(MacroSet::new(), |macros, (axis, value)| macros.define(&axis.name, value));
+		let macro_key = macros.iter().fold(PermutationKey::new(), |key, (name, value)| key.set(name, value));
+
+		// Sessions are only added here, so their modules stay valid
+		let (session, module) = match self.sessions.entry(macro_key.id()) {
+			std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+			std::collections::hash_map::Entry::Vacant(entry) => {
+				let mut config = self.config.clone();
+				config.macros = config.macros.merged(&macros);
+				let session = config.create_session(&self.global_session);
+				entry.insert(session.and_then(|session| {
+					let module = session.load_module(&self.module)?;
+					Ok((session, module))
+				}))
+			},
+		}.as_ref().map_err(Error::clone)?;
+
+		let entry_point = module.find_entry_point_by_name(&self.entry_point).ok_or(Error::Code(SLANG_E_NOT_FOUND))?;
+		let mut components = vec![ComponentType::from(module.clone()), entry_point.into()];
+
+		let mut constants = String::new();
+		for (axis, value) in axes_of(|kind| matches!(kind, AxisKind::Constant(_))) {
+			let AxisKind::Constant(ty) = &axis.kind else { unreachable!() };
+			writeln!(constants, "export static const {ty} {} = {value};", axis.name).unwrap();
+		}
+		let mut diagnostics = Diagnostics::default();
+		if !constants.is_empty() {
+			let name = format!("permutation_constants_{id}");
+			let (constants, constant_diagnostics) = session
+				.load_module_from_source_string_with_diagnostics(&name, &format!("{name}.slang"), &constants)?;
+			diagnostics.extend(constant_diagnostics);
+			components.push(constants.into());
+		}
+
+		let mut program = session.create_composite_component_type(&components)?;
+
+		let specializations = axes_of(|kind| *kind == AxisKind::Specialization).collect::<Vec<_>>();
+		if !specializations.is_empty() {
+			let params = program.layout(0)?.specialization_params();
+			let args = params.iter().map(|param| {
+				specializations.iter()
+					.find(|(axis, _)| Some(axis.name.as_str()) == param.name)
+					.map(|(_, value)| SpecializationArg::Expr(value))
+					.ok_or(Error::Code(SLANG_E_NOT_FOUND))
+			}).collect::<Result<Vec<_>>>()?;
+
+			let (specialized, specialize_diagnostics) = program.specialize_with_diagnostics(&args)?;
+			diagnostics.extend(specialize_diagnostics);
+			program = specialized;
+		}
+
+		let generated = link_and_generate(&program, 1, self.config.targets.len(), &mut diagnostics)?;
+		let reflection = ProgramReflection::new(generated.linked_program.layout(0)?);
+		let code = generated.code.into_iter().map(|mut code| code.swap_remove(0)).collect();
+		Ok(Variant { id, code, reflection, diagnostics, timings: OperationTimings::default() })
+	}
+}
+
+/// The digits of `index` in the mixed radix number system of `radices`, the first digit varying slowest. Enumerates
+/// all combinations of axis values, with `radices` the number of values of each axis.
+pub(crate) fn mixed_radix_digits(index: usize, radices: &[usize]) -> Vec<usize> {
+	let mut remainder = index;
+	let mut digits = vec![0; radices.len()];
+	for (digit, radix) in digits.iter_mut().zip(radices).rev() {
+		*digit = remainder % radix;
+		remainder /= radix;
+	}
+	digits
+}
+
+fn strings<V: ToString>(values: impl IntoIterator<Item = V>) -> Vec<String> {
+	values.into_iter().map(|value| value.to_string()).collect()
+}
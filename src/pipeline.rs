@@ -0,0 +1,51 @@
+//! Steps shared by everything in this crate that compiles whole programs, e.g. the [`CompilePool`](crate::CompilePool)
+//! and [`build`](crate::build), so that they build sessions and generate code the same way.
+
+use std::path::PathBuf;
+
+use crate::{CompileTarget, CompilerOptions, ComponentType, Diagnostics, Result, SessionConfig, TargetConfig};
+
+/// A session config with a target per format and optional profile, see [`SessionConfig::create_session`].
+pub(crate) fn session_config<'a>(
+	targets: impl IntoIterator<Item = (CompileTarget, Option<&'a str>)>,
+	search_paths: impl IntoIterator<Item = impl Into<PathBuf>>,
+	options: &CompilerOptions,
+) -> SessionConfig {
+	let config = targets.into_iter().fold(SessionConfig::new(), |config, (format, profile)| {
+		let target = TargetConfig::new(format);
+		config.target(match profile {
+			Some(profile) => target.profile(profile),
+			None => target,
+		})
+	});
+	search_paths.into_iter().fold(config, |config, path| config.search_path(path)).options(options.clone())
+}
+
+/// A linked program and the code of its entry points.
+pub(crate) struct GeneratedCode {
+	pub linked_program: ComponentType,
+	/// The code of every entry point, per target.
+	pub code: Vec<Vec<Vec<u8>>>,
+}
+
+/// Links `program` and generates the code of its first `entry_points` entry points for its first `targets` targets,
+/// adding all warnings to `diagnostics`.
+pub(crate) fn link_and_generate(
+	program: &ComponentType,
+	entry_points: usize,
+	targets: usize,
+	diagnostics: &mut Diagnostics,
+) -> Result<GeneratedCode> {
+	let (linked_program, link_diagnostics) = program.link_with_diagnostics()?;
+	diagnostics.extend(link_diagnostics);
+
+	let code = (0..targets as i64).map(|target| {
+		(0..entry_points as i64).map(|entry_point| {
+			let (blob, code_diagnostics) = linked_program.entry_point_code_with_diagnostics(entry_point, target)?;
+			diagnostics.extend(code_diagnostics);
+			Ok(blob.as_slice().to_owned())
+		}).collect::<Result<Vec<_>>>()
+	}).collect::<Result<Vec<_>>>()?;
+
+	Ok(GeneratedCode { linked_program, code })
+}
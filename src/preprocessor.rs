@@ -0,0 +1,261 @@
+use std::ffi::CString;
+
+use crate::permutation::mixed_radix_digits;
+use crate::pipeline::link_and_generate;
+use crate::reflection::ProgramReflection;
+use crate::{
+	ComponentType, Diagnostics, Error, GlobalSession, Result, SLANG_E_INVALID_ARG, SLANG_E_NOT_FOUND, SessionConfig, sys,
+};
+
+/// Preprocessor macros defined for a whole session, see [`SessionDesc::macros`](crate::SessionDesc::macros).
+///
+/// Every name is defined at most once; defining it again overrides the value. Macros are kept sorted by name, so
+/// equal sets produce identical session descriptions.
+#[derive(Default)]
+pub struct MacroSet {
+	macros: Vec<(CString, CString)>,
+	descs: Vec<sys::slang_PreprocessorMacroDesc>,
+	/// Whether a definition was left out for containing a NUL character.
+	rejected: bool,
+}
+
+// SAFETY: The descriptions only point into the strings in `macros`, which are owned and never mutated, so the set can
+// be moved to and read from other threads like `CompilerOptions`.
+unsafe impl Send for MacroSet {}
+unsafe impl Sync for MacroSet {}
+
+impl Clone for MacroSet {
+	fn clone(&self) -> Self {
+		let mut clone = Self { macros: self.macros.clone(), descs: Vec::new(), rejected: self.rejected };
+		clone.update_descs();
+		clone
+	}
+}
+
+impl PartialEq for MacroSet {
+	fn eq(&self, other: &Self) -> bool {
+		self.macros == other.macros && self.rejected == other.rejected
+	}
+}
+
+impl Eq for MacroSet {}
+
+impl std::fmt::Debug for MacroSet {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_map().entries(self.iter()).finish()
+	}
+}
+
+impl std::fmt::Display for MacroSet {
+	/// Formats the macros as `NAME=VALUE` pairs separated by spaces, e.g. for logging a permutation.
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (index, (name, value)) in self.iter().enumerate() {
+			let separator = if index == 0 { "" } else { " " };
+			write!(f, "{separator}{name}={value}")?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for MacroSet {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		serializer.collect_map(self.iter())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MacroSet {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+		let macros = std::collections::BTreeMap::<String, String>::deserialize(deserializer)?;
+		if macros.iter().any(|(name, value)| name.contains('\0') || value.contains('\0')) {
+			return Err(serde::de::Error::custom("macro contains a NUL character"));
+		}
+		Ok(macros.iter().fold(Self::new(), |macros, (name, value)| macros.define(name, value)))
+	}
+}
+
+impl MacroSet {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Defines `name` as `value`, replacing any previous definition. See [`set`](Self::set) for invalid definitions.
+	pub fn define(mut self, name: &str, value: &str) -> Self {
+		self.set(name, value);
+		self
+	}
+
+	/// Like [`define`](Self::define), but fails if `name` or `value` contains a NUL character.
+	pub fn try_define(mut self, name: &str, value: &str) -> Result<Self> {
+		self.try_set(name, value)?;
+		Ok(self)
+	}
+
+	/// Removes the definition of `name` from the set. Macros defined in the shader source itself are not affected.
+	pub fn undefine(mut self, name: &str) -> Self {
+		self.remove(name);
+		self
+	}
+
+	/// A definition whose name or value contains a NUL character can't be passed to *Slang*. It is left out and the set
+	/// becomes [invalid](Self::is_valid), so that creating a session from a [`SessionConfig`] with it fails.
+	pub fn set(&mut self, name: &str, value: &str) {
+		if self.try_set(name, value).is_err() {
+			self.rejected = true;
+		}
+	}
+
+	/// Like [`set`](Self::set), but fails if `name` or `value` contains a NUL character, leaving the set unchanged.
+	pub fn try_set(&mut self, name: &str, value: &str) -> Result<()> {
+		let name = CString::new(name).map_err(|_| Error::Code(SLANG_E_INVALID_ARG))?;
+		let value = CString::new(value).map_err(|_| Error::Code(SLANG_E_INVALID_ARG))?;
+		match self.macros.binary_search_by(|(n, _)| n.cmp(&name)) {
+			Ok(index) => self.macros[index].1 = value,
+			Err(index) => self.macros.insert(index, (name, value)),
+		}
+		self.update_descs();
+		Ok(())
+	}
+
+	/// Whether no definition was left out by [`set`](Self::set) or [`define`](Self::define).
+	pub fn is_valid(&self) -> bool {
+		!self.rejected
+	}
+
+	pub fn remove(&mut self, name: &str) -> Option<String> {
+		let index = self.macros.iter().position(|(n, _)| n.to_bytes() == name.as_bytes())?;
+		let (_, value) = self.macros.remove(index);
+		self.update_descs();
+		Some(value.to_string_lossy().into_owned())
+	}
+
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.iter().find(|(n, _)| *n == name).map(|(_, value)| value)
+	}
+
+	pub fn len(&self) -> usize {
+		self.macros.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.macros.is_empty()
+	}
+
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, &str)> {
+		self.macros.iter().map(|(name, value)| (name.to_str().unwrap(), value.to_str().unwrap()))
+	}
+
+	/// A copy of this set with all macros of `overrides` defined on top, e.g. to add the macros of one permutation to
+	/// the ones shared by all.
+	pub fn merged(&self, overrides: &MacroSet) -> Self {
+		let mut merged = overrides.iter().fold(self.clone(), |macros, (name, value)| macros.define(name, value));
+		merged.rejected |= overrides.rejected;
+		merged
+	}
+
+	pub(crate) fn descs(&self) -> &[sys::slang_PreprocessorMacroDesc] {
+		&self.descs
+	}
+
+	fn update_descs(&mut self) {
+		self.descs = self.macros.iter()
+			.map(|(name, value)| sys::slang_PreprocessorMacroDesc { name: name.as_ptr(), value: value.as_ptr() })
+			.collect();
+	}
+}
+
+impl<'a> FromIterator<(&'a str, &'a str)> for MacroSet {
+	fn from_iter<I: IntoIterator<Item = (&'a str, &'a str)>>(iter: I) -> Self {
+		iter.into_iter().fold(Self::new(), |macros, (name, value)| macros.define(name, value))
+	}
+}
+
+/// Every combination of values for a set of macros, e.g. `SHADOWS` in `0, 1` times `LIGHTS` in `1, 4, 16`.
+#[derive(Debug, Clone, Default)]
+pub struct MacroPermutations {
+	axes: Vec<(String, Vec<String>)>,
+}
+
+impl MacroPermutations {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a macro and the values it takes. The first axis varies slowest.
+	pub fn axis<V: ToString>(mut self, name: &str, values: impl IntoIterator<Item = V>) -> Self {
+		self.axes.push((name.to_owned(), values.into_iter().map(|v| v.to_string()).collect()));
+		self
+	}
+
+	/// The number of combinations.
+	pub fn len(&self) -> usize {
+		self.axes.iter().map(|(_, values)| values.len()).product()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The macros of combination `index`, which is in `0..self.len()`.
+	pub fn get(&self, index: usize) -> Option<MacroSet> {
+		if index >= self.len() {
+			return None;
+		}
+
+		let radices = self.axes.iter().map(|(_, values)| values.len()).collect::<Vec<_>>();
+		let digits = mixed_radix_digits(index, &radices);
+		Some(self.axes.iter().zip(digits).fold(MacroSet::new(), |macros, ((name, values), digit)| {
+			macros.define(name, &values[digit])
+		}))
+	}
+
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = MacroSet> {
+		(0..self.len()).map(|index| self.get(index).unwrap())
+	}
+
+	/// Compiles an entry point of a module once per combination, with the combination's macros defined on top of the
+	/// macros of `config`. Every combination is compiled in its own session when the iterator reaches it.
+	pub fn compile<'a>(
+		&'a self,
+		global_session: &'a GlobalSession,
+		config: &'a SessionConfig,
+		module: &'a str,
+		entry_point: &'a str,
+	) -> impl ExactSizeIterator<Item = (MacroSet, Result<CompiledPermutation>)> + 'a {
+		self.iter().map(move |macros| {
+			let mut config = config.clone();
+			config.macros = config.macros.merged(&macros);
+			let result = compile_permutation(global_session, &config, module, entry_point);
+			(macros, result)
+		})
+	}
+}
+
+/// One combination of [`MacroPermutations`], compiled for all targets of the session.
+#[derive(Debug, Clone)]
+pub struct CompiledPermutation {
+	/// The code of the entry point for every target of the session.
+	pub code: Vec<Vec<u8>>,
+	/// The reflection of the first target.
+	pub reflection: ProgramReflection,
+	/// Warnings reported while compiling.
+	pub diagnostics: Diagnostics,
+}
+
+fn compile_permutation(
+	global_session: &GlobalSession,
+	config: &SessionConfig,
+	module: &str,
+	entry_point: &str,
+) -> Result<CompiledPermutation> {
+	let session = config.create_session(global_session)?;
+	let (module, mut diagnostics) = session.load_module_with_diagnostics(module)?;
+	let entry_point = module.find_entry_point_by_name(entry_point).ok_or(Error::Code(SLANG_E_NOT_FOUND))?;
+	let program = session.create_composite_component_type(&[module.into(), ComponentType::from(entry_point)])?;
+
+	let generated = link_and_generate(&program, 1, config.targets.len(), &mut diagnostics)?;
+	let reflection = ProgramReflection::new(generated.linked_program.layout(0)?);
+	let code = generated.code.into_iter().map(|mut code| code.swap_remove(0)).collect();
+	Ok(CompiledPermutation { code, reflection, diagnostics })
+}
@@ -0,0 +1,467 @@
+//! Reflection output in the JSON format emitted by `slangc -reflection-json`.
+
+use super::{EntryPoint, Shader, Type, TypeLayout, VariableLayout, compute_string_hash};
+use crate::{ParameterCategory, ResourceAccess, ScalarType, Stage, TypeKind};
+
+/// *Slang* reports unbounded resource arrays with this size.
+const UNBOUNDED_SIZE: usize = usize::MAX;
+
+/// The bits of a `SlangResourceShape` that hold the base shape, the remaining bits are flags.
+const RESOURCE_BASE_SHAPE_MASK: u32 = 0x0F;
+const RESOURCE_FEEDBACK_FLAG: u32 = 0x10;
+const RESOURCE_ARRAY_FLAG: u32 = 0x40;
+const RESOURCE_MULTISAMPLE_FLAG: u32 = 0x80;
+const RESOURCE_COMBINED_FLAG: u32 = 0x100;
+
+enum Value {
+	Bool(bool),
+	Number(u64),
+	String(String),
+	Array(Vec<Value>),
+	Object(Vec<(String, Value)>),
+}
+
+impl From<&str> for Value {
+	fn from(value: &str) -> Self {
+		Value::String(value.to_owned())
+	}
+}
+
+impl From<u64> for Value {
+	fn from(value: u64) -> Self {
+		Value::Number(value)
+	}
+}
+
+/// Builds up the members of a JSON object in insertion order.
+#[derive(Default)]
+struct Object(Vec<(String, Value)>);
+
+impl Object {
+	fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+		self.0.push((key.to_owned(), value.into()));
+		self
+	}
+
+	fn push(&mut self, key: &str, value: impl Into<Value>) {
+		self.0.push((key.to_owned(), value.into()));
+	}
+}
+
+impl From<Object> for Value {
+	fn from(object: Object) -> Self {
+		Value::Object(object.0)
+	}
+}
+
+impl From<Vec<Value>> for Value {
+	fn from(values: Vec<Value>) -> Self {
+		Value::Array(values)
+	}
+}
+
+pub(super) fn program(shader: &Shader) -> String {
+	let mut root = Object::default()
+		.with("parameters", shader.parameters().map(|p| parameter(p).into()).collect::<Vec<_>>())
+		.with("entryPoints", shader.entry_points().map(|e| entry_point(e).into()).collect::<Vec<_>>());
+
+	if shader.hashed_string_count() > 0 {
+		let hashed_strings = shader.hashed_strings()
+			.map(|s| (s.to_owned(), Value::Number(compute_string_hash(s) as u64)))
+			.collect();
+		root.push("hashedStrings", Value::Object(hashed_strings));
+	}
+
+	let mut out = String::new();
+	write_value(&mut out, &root.into(), 0);
+	out.push('\n');
+	out
+}
+
+fn entry_point(entry_point: &EntryPoint) -> Object {
+	let stage = entry_point.stage();
+	let mut object = Object::default()
+		.with("name", entry_point.name().unwrap_or_default())
+		.with("stage", stage_name(stage))
+		.with("parameters", entry_point.parameters().map(|p| parameter(p).into()).collect::<Vec<_>>());
+
+	if let Some(result) = entry_point.result_var_layout() {
+		let is_void = result.ty().is_some_and(|t| t.kind() == TypeKind::Scalar && t.scalar_type() == ScalarType::Void);
+		if !is_void {
+			object.push("result", parameter(result));
+		}
+	}
+	if stage == Stage::Compute {
+		let size = entry_point.compute_thread_group_size().map(Value::Number);
+		object.push("threadGroupSize", Vec::from(size));
+	}
+
+	object
+}
+
+/// A parameter, field or entry point parameter together with its binding.
+fn parameter(var: &VariableLayout) -> Object {
+	let mut object = Object::default();
+	if let Some(name) = var.name() {
+		object.push("name", name);
+	}
+	push_bindings(&mut object, var);
+	if let Some(type_layout) = var.type_layout() {
+		object.push("type", type_layout_json(type_layout));
+	}
+	object
+}
+
+fn push_bindings(object: &mut Object, var: &VariableLayout) {
+	if var.stage() != Stage::None {
+		object.push("stage", stage_name(var.stage()));
+	}
+
+	let type_layout = var.type_layout();
+	let bindings = var.categories().map(|category| {
+		let count = type_layout.map_or(0, |t| t.size(category));
+		binding(category, var.offset(category), count, var.binding_space_with_category(category)).into()
+	}).collect::<Vec<Value>>();
+
+	match bindings.len() {
+		0 => {}
+		1 => object.push("binding", bindings.into_iter().next().unwrap()),
+		_ => object.push("bindings", bindings),
+	}
+
+	if let Some(semantic_name) = var.semantic_name() {
+		object.push("semanticName", semantic_name);
+		if var.semantic_index() != 0 {
+			object.push("semanticIndex", var.semantic_index() as u64);
+		}
+	}
+}
+
+fn binding(category: ParameterCategory, offset: usize, count: usize, space: usize) -> Object {
+	let object = Object::default().with("kind", category_name(category));
+
+	if category == ParameterCategory::Uniform {
+		return object.with("offset", offset as u64).with("size", count as u64);
+	}
+
+	let mut object = match space {
+		0 => object,
+		space => object.with("space", space as u64),
+	};
+	object.push("index", offset as u64);
+	match count {
+		1 => {}
+		UNBOUNDED_SIZE => object.push("count", "unbounded"),
+		count => object.push("count", count as u64),
+	}
+	object
+}
+
+fn type_layout_json(type_layout: &TypeLayout) -> Object {
+	let kind = type_layout.kind();
+
+	match kind {
+		TypeKind::Struct => {
+			let mut object = Object::default().with("kind", "struct");
+			if let Some(name) = type_layout.name() {
+				object.push("name", name);
+			}
+			object.with("fields", type_layout.fields().map(|f| parameter(f).into()).collect::<Vec<_>>())
+		}
+		TypeKind::Array => {
+			let mut object = Object::default()
+				.with("kind", "array")
+				.with("elementCount", type_layout.element_count().unwrap_or(0) as u64);
+			if let Some(element) = type_layout.element_type_layout() {
+				object.push("elementType", type_layout_json(element));
+			}
+			let stride = type_layout.element_stride(ParameterCategory::Uniform);
+			if stride != 0 {
+				object.push("uniformStride", stride as u64);
+			}
+			object
+		}
+		TypeKind::ConstantBuffer | TypeKind::ParameterBlock | TypeKind::TextureBuffer | TypeKind::ShaderStorageBuffer => {
+			let mut object = Object::default().with("kind", type_kind_name(kind));
+			if let Some(container) = type_layout.container_var_layout() {
+				let mut container_object = Object::default();
+				push_bindings(&mut container_object, container);
+				object.push("containerVarLayout", container_object);
+			}
+			if let Some(element) = type_layout.element_var_layout() {
+				object.push("elementVarLayout", parameter(element));
+			}
+			object
+		}
+		// The remaining kinds have no layout specific information
+		_ => match type_layout.ty() {
+			Some(ty) => type_json(ty),
+			None => Object::default().with("kind", type_kind_name(kind)),
+		},
+	}
+}
+
+fn type_json(ty: &Type) -> Object {
+	let kind = ty.kind();
+	let object = Object::default().with("kind", type_kind_name(kind));
+
+	match kind {
+		TypeKind::Struct => {
+			let fields = ty.fields().map(|field| {
+				let mut object = Object::default().with("name", field.name().unwrap_or_default());
+				if let Some(ty) = field.ty() {
+					object.push("type", type_json(ty));
+				}
+				object.into()
+			}).collect::<Vec<_>>();
+			object.with("name", ty.name().unwrap_or_default()).with("fields", fields)
+		}
+		TypeKind::Array | TypeKind::Vector => {
+			let mut object = object.with("elementCount", ty.element_count() as u64);
+			if let Some(element) = ty.element_type() {
+				object.push("elementType", type_json(element));
+			}
+			object
+		}
+		TypeKind::Matrix => {
+			let mut object = object
+				.with("rowCount", ty.row_count() as u64)
+				.with("columnCount", ty.column_count() as u64);
+			if let Some(element) = ty.element_type() {
+				object.push("elementType", type_json(element));
+			}
+			object
+		}
+		TypeKind::Scalar => object.with("scalarType", scalar_type_name(ty.scalar_type())),
+		TypeKind::Resource => resource_json(object, ty),
+		TypeKind::ConstantBuffer | TypeKind::ParameterBlock | TypeKind::TextureBuffer | TypeKind::ShaderStorageBuffer => {
+			match ty.element_type() {
+				Some(element) => object.with("elementType", type_json(element)),
+				None => object,
+			}
+		}
+		TypeKind::GenericTypeParameter | TypeKind::Interface | TypeKind::Feedback => {
+			object.with("name", ty.name().unwrap_or_default())
+		}
+		TypeKind::Pointer => match ty.element_type().and_then(|t| t.name()) {
+			Some(name) => object.with("valueType", name),
+			None => object,
+		},
+		_ => object,
+	}
+}
+
+fn resource_json(mut object: Object, ty: &Type) -> Object {
+	let shape = ty.resource_shape() as u32;
+
+	object.push("baseShape", base_shape_name(shape & RESOURCE_BASE_SHAPE_MASK));
+	for (flag, name) in [
+		(RESOURCE_ARRAY_FLAG, "array"),
+		(RESOURCE_MULTISAMPLE_FLAG, "multisample"),
+		(RESOURCE_FEEDBACK_FLAG, "feedback"),
+		(RESOURCE_COMBINED_FLAG, "combined"),
+	] {
+		if shape & flag != 0 {
+			object.push(name, Value::Bool(true));
+		}
+	}
+
+	let access = ty.resource_access();
+	if access != ResourceAccess::Read && access != ResourceAccess::None {
+		object.push("access", access_name(access));
+	}
+	if let Some(result_type) = ty.resource_result_type() {
+		object.push("resultType", type_json(result_type));
+	}
+	object
+}
+
+fn base_shape_name(base_shape: u32) -> &'static str {
+	match base_shape {
+		1 => "texture1D",
+		2 => "texture2D",
+		3 => "texture3D",
+		4 => "textureCube",
+		5 => "textureBuffer",
+		6 => "structuredBuffer",
+		7 => "byteAddressBuffer",
+		9 => "accelerationStructure",
+		10 => "subpass",
+		_ => "unknown",
+	}
+}
+
+fn access_name(access: ResourceAccess) -> &'static str {
+	match access {
+		ResourceAccess::Read => "read",
+		ResourceAccess::ReadWrite => "readWrite",
+		ResourceAccess::RasterOrdered => "rasterOrdered",
+		ResourceAccess::Append => "append",
+		ResourceAccess::Consume => "consume",
+		ResourceAccess::Write => "write",
+		ResourceAccess::Feedback => "feedback",
+		_ => "unknown",
+	}
+}
+
+fn type_kind_name(kind: TypeKind) -> &'static str {
+	match kind {
+		TypeKind::Struct => "struct",
+		TypeKind::Array => "array",
+		TypeKind::Matrix => "matrix",
+		TypeKind::Vector => "vector",
+		TypeKind::Scalar => "scalar",
+		TypeKind::ConstantBuffer => "constantBuffer",
+		TypeKind::Resource => "resource",
+		TypeKind::SamplerState => "samplerState",
+		TypeKind::TextureBuffer => "textureBuffer",
+		TypeKind::ShaderStorageBuffer => "shaderStorageBuffer",
+		TypeKind::ParameterBlock => "parameterBlock",
+		TypeKind::GenericTypeParameter => "GenericTypeParameter",
+		TypeKind::Interface => "interface",
+		TypeKind::OutputStream => "outputStream",
+		TypeKind::MeshOutput => "meshOutput",
+		TypeKind::Specialized => "specialized",
+		TypeKind::Feedback => "feedback",
+		TypeKind::Pointer => "pointer",
+		TypeKind::DynamicResource => "dynamicResource",
+		TypeKind::Enum => "enum",
+		_ => "unknown",
+	}
+}
+
+fn scalar_type_name(scalar_type: ScalarType) -> &'static str {
+	match scalar_type {
+		ScalarType::Void => "void",
+		ScalarType::Bool => "bool",
+		ScalarType::Int8 => "int8",
+		ScalarType::Uint8 => "uint8",
+		ScalarType::Int16 => "int16",
+		ScalarType::Uint16 => "uint16",
+		ScalarType::Int32 => "int32",
+		ScalarType::Uint32 => "uint32",
+		ScalarType::Int64 => "int64",
+		ScalarType::Uint64 => "uint64",
+		ScalarType::Float16 => "float16",
+		ScalarType::Float32 => "float32",
+		ScalarType::Float64 => "float64",
+		_ => "unknown",
+	}
+}
+
+fn category_name(category: ParameterCategory) -> &'static str {
+	match category {
+		ParameterCategory::Mixed => "mixed",
+		ParameterCategory::ConstantBuffer => "constantBuffer",
+		ParameterCategory::ShaderResource => "shaderResource",
+		ParameterCategory::UnorderedAccess => "unorderedAccess",
+		ParameterCategory::VaryingInput => "varyingInput",
+		ParameterCategory::VaryingOutput => "varyingOutput",
+		ParameterCategory::SamplerState => "samplerState",
+		ParameterCategory::Uniform => "uniform",
+		ParameterCategory::DescriptorTableSlot => "descriptorTableSlot",
+		ParameterCategory::SpecializationConstant => "specializationConstant",
+		ParameterCategory::PushConstantBuffer => "pushConstantBuffer",
+		ParameterCategory::RegisterSpace => "registerSpace",
+		ParameterCategory::Generic => "generic",
+		ParameterCategory::RayPayload => "rayPayload",
+		ParameterCategory::HitAttributes => "hitAttributes",
+		ParameterCategory::CallablePayload => "callablePayload",
+		ParameterCategory::ShaderRecord => "shaderRecord",
+		ParameterCategory::ExistentialTypeParam => "existentialTypeParam",
+		ParameterCategory::ExistentialObjectParam => "existentialObjectParam",
+		ParameterCategory::SubElementRegisterSpace => "subElementRegisterSpace",
+		ParameterCategory::Subpass => "subpass",
+		ParameterCategory::MetalArgumentBufferElement => "metalArgumentBufferElement",
+		ParameterCategory::MetalAttribute => "metalAttribute",
+		ParameterCategory::MetalPayload => "metalPayload",
+		_ => "unknown",
+	}
+}
+
+fn stage_name(stage: Stage) -> &'static str {
+	match stage {
+		Stage::Vertex => "vertex",
+		Stage::Hull => "hull",
+		Stage::Domain => "domain",
+		Stage::Geometry => "geometry",
+		Stage::Fragment => "fragment",
+		Stage::Compute => "compute",
+		Stage::RayGeneration => "raygeneration",
+		Stage::Intersection => "intersection",
+		Stage::AnyHit => "anyhit",
+		Stage::ClosestHit => "closesthit",
+		Stage::Miss => "miss",
+		Stage::Callable => "callable",
+		Stage::Mesh => "mesh",
+		Stage::Amplification => "amplification",
+		Stage::Dispatch => "dispatch",
+		_ => "unknown",
+	}
+}
+
+/// Pretty prints a value with the same four space indentation `slangc` uses.
+fn write_value(out: &mut String, value: &Value, indent: usize) {
+	use std::fmt::Write;
+
+	match value {
+		Value::Bool(value) => write!(out, "{value}").unwrap(),
+		Value::Number(value) => write!(out, "{value}").unwrap(),
+		Value::String(value) => write_string(out, value),
+		Value::Array(values) if values.is_empty() => out.push_str("[]"),
+		// Arrays of scalars like thread group sizes stay on one line
+		Value::Array(values) if values.iter().all(|v| matches!(v, Value::Bool(_) | Value::Number(_))) => {
+			out.push('[');
+			for (i, value) in values.iter().enumerate() {
+				if i > 0 {
+					out.push_str(", ");
+				}
+				write_value(out, value, indent);
+			}
+			out.push(']');
+		}
+		Value::Array(values) => {
+			out.push_str("[\n");
+			for (i, value) in values.iter().enumerate() {
+				push_indent(out, indent + 1);
+				write_value(out, value, indent + 1);
+				out.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+			}
+			push_indent(out, indent);
+			out.push(']');
+		}
+		Value::Object(members) if members.is_empty() => out.push_str("{}"),
+		Value::Object(members) => {
+			out.push_str("{\n");
+			for (i, (key, value)) in members.iter().enumerate() {
+				push_indent(out, indent + 1);
+				write_string(out, key);
+				out.push_str(": ");
+				write_value(out, value, indent + 1);
+				out.push_str(if i + 1 < members.len() { ",\n" } else { "\n" });
+			}
+			push_indent(out, indent);
+			out.push('}');
+		}
+	}
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+	out.extend(std::iter::repeat_n("    ", indent));
+}
+
+fn write_string(out: &mut String, value: &str) {
+	out.push('"');
+	for c in value.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+}
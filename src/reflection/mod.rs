@@ -2,6 +2,8 @@ mod decl;
 mod entry_point;
 mod function;
 mod generic;
+mod json;
+mod program;
 mod shader;
 mod ty;
 mod type_layout;
@@ -14,7 +16,11 @@ pub use decl::Decl;
 pub use entry_point::EntryPoint;
 pub use function::Function;
 pub use generic::Generic;
-pub use shader::Shader;
+pub use program::{
+	AttributeArgument, Binding, EntryPointReflection, ProgramReflection, Size, TypeLayoutReflection,
+	UserAttributeReflection, VariableLayoutReflection,
+};
+pub use shader::{Shader, SpecializationParam, SpecializationParamKind};
 pub use ty::Type;
 pub use type_layout::TypeLayout;
 pub use type_parameter::TypeParameter;
@@ -0,0 +1,237 @@
+//! An owned snapshot of the reflection data of a program.
+//!
+//! The types in the parent module are views into data owned by a *Slang* [`ComponentType`](crate::ComponentType).
+//! The types here copy everything that's needed to lay out and bind shader parameters into plain Rust data, which can
+//! be stored next to the compiled code and loaded without *Slang*.
+
+use super::{EntryPoint, Shader, TypeLayout, UserAttribute, VariableLayout};
+use crate::{ParameterCategory, ResourceAccess, ResourceShape, ScalarType, Stage, TypeKind};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProgramReflection {
+	pub parameters: Vec<VariableLayoutReflection>,
+	pub entry_points: Vec<EntryPointReflection>,
+	pub global_constant_buffer_binding: u64,
+	pub global_constant_buffer_size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntryPointReflection {
+	pub name: String,
+	pub stage: Stage,
+	pub thread_group_size: [u64; 3],
+	pub parameters: Vec<VariableLayoutReflection>,
+	pub result: Option<VariableLayoutReflection>,
+	pub user_attributes: Vec<UserAttributeReflection>,
+}
+
+/// A shader parameter, entry point parameter or struct field together with its location.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VariableLayoutReflection {
+	pub name: String,
+	/// One entry per resource kind the variable consumes, e.g. a uniform byte offset and a descriptor table slot.
+	pub bindings: Vec<Binding>,
+	pub semantic_name: Option<String>,
+	pub semantic_index: usize,
+	pub type_layout: TypeLayoutReflection,
+	pub user_attributes: Vec<UserAttributeReflection>,
+}
+
+/// The location of a variable for one [`ParameterCategory`]. For [`ParameterCategory::Uniform`] the offset is in
+/// bytes, for all other categories it is a register or binding index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Binding {
+	pub category: ParameterCategory,
+	pub offset: usize,
+	pub space: usize,
+}
+
+/// The size of a type for one [`ParameterCategory`], in the same unit as [`Binding::offset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Size {
+	pub category: ParameterCategory,
+	pub size: usize,
+	pub stride: usize,
+	pub alignment: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypeLayoutReflection {
+	pub name: Option<String>,
+	pub kind: TypeKind,
+	pub sizes: Vec<Size>,
+	pub fields: Vec<VariableLayoutReflection>,
+	/// The element type of arrays, vectors, matrices, buffers and parameter blocks.
+	pub element: Option<Box<TypeLayoutReflection>>,
+	pub element_count: Option<usize>,
+	pub row_count: Option<u32>,
+	pub column_count: Option<u32>,
+	pub scalar_type: Option<ScalarType>,
+	pub resource_shape: Option<ResourceShape>,
+	pub resource_access: Option<ResourceAccess>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UserAttributeReflection {
+	pub name: String,
+	pub arguments: Vec<AttributeArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AttributeArgument {
+	Int(i32),
+	Float(f32),
+	String(String),
+	/// An argument of a type that can't be reflected, kept so that the positions of the others match the declaration.
+	Unknown,
+}
+
+impl ProgramReflection {
+	pub fn new(shader: &Shader) -> Self {
+		Self {
+			parameters: shader.parameters().map(VariableLayoutReflection::new).collect(),
+			entry_points: shader.entry_points().map(EntryPointReflection::new).collect(),
+			global_constant_buffer_binding: shader.global_constant_buffer_binding(),
+			global_constant_buffer_size: shader.global_constant_buffer_size(),
+		}
+	}
+
+	pub fn find_parameter_by_name(&self, name: &str) -> Option<&VariableLayoutReflection> {
+		self.parameters.iter().find(|p| p.name == name)
+	}
+
+	pub fn find_entry_point_by_name(&self, name: &str) -> Option<&EntryPointReflection> {
+		self.entry_points.iter().find(|e| e.name == name)
+	}
+}
+
+impl From<&Shader> for ProgramReflection {
+	fn from(shader: &Shader) -> Self {
+		Self::new(shader)
+	}
+}
+
+impl EntryPointReflection {
+	pub fn new(entry_point: &EntryPoint) -> Self {
+		let user_attributes = entry_point.function()
+			.map(|f| f.user_attributes().map(UserAttributeReflection::new).collect())
+			.unwrap_or_default();
+
+		Self {
+			name: entry_point.name().unwrap_or_default().to_owned(),
+			stage: entry_point.stage(),
+			thread_group_size: entry_point.compute_thread_group_size(),
+			parameters: entry_point.parameters().map(VariableLayoutReflection::new).collect(),
+			result: entry_point.result_var_layout().map(VariableLayoutReflection::new),
+			user_attributes,
+		}
+	}
+}
+
+impl VariableLayoutReflection {
+	pub fn new(variable: &VariableLayout) -> Self {
+		let bindings = variable.categories().map(|category| Binding {
+			category,
+			offset: variable.offset(category),
+			space: variable.binding_space_with_category(category),
+		}).collect();
+		let user_attributes = variable.variable()
+			.map(|v| v.user_attributes().map(UserAttributeReflection::new).collect())
+			.unwrap_or_default();
+
+		Self {
+			name: variable.name().unwrap_or_default().to_owned(),
+			bindings,
+			semantic_name: variable.semantic_name().map(str::to_owned),
+			semantic_index: variable.semantic_index(),
+			type_layout: variable.type_layout().map(TypeLayoutReflection::new).unwrap_or_else(TypeLayoutReflection::none),
+			user_attributes,
+		}
+	}
+
+	pub fn binding(&self, category: ParameterCategory) -> Option<&Binding> {
+		self.bindings.iter().find(|b| b.category == category)
+	}
+}
+
+impl TypeLayoutReflection {
+	pub fn new(type_layout: &TypeLayout) -> Self {
+		let kind = type_layout.kind();
+		let sizes = type_layout.categories().map(|category| Size {
+			category,
+			size: type_layout.size(category),
+			stride: type_layout.stride(category),
+			alignment: type_layout.alignment(category),
+		}).collect();
+
+		// Pointers may refer back to the type containing them, so their target is not expanded
+		let element = match kind {
+			TypeKind::Pointer => None,
+			_ => type_layout.element_type_layout().map(|e| Box::new(Self::new(e))),
+		};
+		let is_resource = matches!(kind, TypeKind::Resource);
+		let has_shape = matches!(kind, TypeKind::Vector | TypeKind::Matrix | TypeKind::Scalar);
+
+		Self {
+			name: type_layout.name().map(str::to_owned),
+			kind,
+			sizes,
+			fields: type_layout.fields().map(VariableLayoutReflection::new).collect(),
+			element,
+			element_count: type_layout.element_count().filter(|_| matches!(kind, TypeKind::Array | TypeKind::Vector)),
+			row_count: type_layout.row_count().filter(|_| has_shape),
+			column_count: type_layout.column_count().filter(|_| has_shape),
+			scalar_type: type_layout.scalar_type().filter(|_| has_shape),
+			resource_shape: type_layout.resource_shape().filter(|_| is_resource),
+			resource_access: type_layout.resource_access().filter(|_| is_resource),
+		}
+	}
+
+	fn none() -> Self {
+		Self {
+			name: None,
+			kind: TypeKind::None,
+			sizes: Vec::new(),
+			fields: Vec::new(),
+			element: None,
+			element_count: None,
+			row_count: None,
+			column_count: None,
+			scalar_type: None,
+			resource_shape: None,
+			resource_access: None,
+		}
+	}
+
+	pub fn size(&self, category: ParameterCategory) -> Option<&Size> {
+		self.sizes.iter().find(|s| s.category == category)
+	}
+
+	pub fn find_field_by_name(&self, name: &str) -> Option<&VariableLayoutReflection> {
+		self.fields.iter().find(|f| f.name == name)
+	}
+}
+
+impl UserAttributeReflection {
+	pub fn new(attribute: &UserAttribute) -> Self {
+		let arguments = (0..attribute.argument_count()).map(|i| {
+			attribute.argument_value_int(i).map(AttributeArgument::Int)
+				.or_else(|| attribute.argument_value_float(i).map(AttributeArgument::Float))
+				.or_else(|| attribute.argument_value_string(i).map(|s| AttributeArgument::String(s.to_owned())))
+				.unwrap_or(AttributeArgument::Unknown)
+		}).collect();
+
+		Self { name: attribute.name().unwrap_or_default().to_owned(), arguments }
+	}
+}
@@ -3,6 +3,23 @@ use super::{
 };
 use crate::{GenericArg, GenericArgType, LayoutRules, sys};
 
+/// A specialization parameter of a program, as listed by [`Shader::specialization_params`].
+#[derive(Clone)]
+pub struct SpecializationParam<'a> {
+	pub name: Option<&'a str>,
+	pub kind: SpecializationParamKind,
+	/// The interfaces a type argument needs to conform to.
+	pub constraints: Vec<&'a Type>,
+	/// The entry point declaring this parameter, or `None` for a global `type_param`.
+	pub entry_point: Option<&'a EntryPoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecializationParamKind {
+	Type,
+	Value,
+}
+
 #[repr(transparent)]
 pub struct Shader(sys::SlangReflection);
 
@@ -36,6 +53,42 @@ impl Shader {
 		rcall!(spReflection_FindTypeParameter(self, name.as_ptr()) as Option<&TypeParameter>)
 	}
 
+	/// All specialization parameters of the program in the order [`ComponentType::specialize`](crate::ComponentType::specialize)
+	/// expects their arguments: global type parameters first, followed by the generic parameters of each entry point.
+	/// Within an entry point, type parameters come before value parameters.
+	pub fn specialization_params(&self) -> Vec<SpecializationParam<'_>> {
+		let global = self.type_parameters().map(|param| SpecializationParam {
+			name: param.name(),
+			kind: SpecializationParamKind::Type,
+			constraints: param.constraints().collect(),
+			entry_point: None,
+		});
+
+		let entry_point_params = self.entry_points().flat_map(|entry_point| {
+			let generic = entry_point.function().and_then(|function| function.generic_container());
+
+			generic.into_iter().flat_map(move |generic| {
+				let types = generic.type_parameters().map(move |param| SpecializationParam {
+					name: param.name(),
+					kind: SpecializationParamKind::Type,
+					constraints: (0..generic.type_parameter_constraint_count(param))
+						.filter_map(|i| generic.type_parameter_constraint_by_index(param, i))
+						.collect(),
+					entry_point: Some(entry_point),
+				});
+				let values = generic.value_parameters().map(move |param| SpecializationParam {
+					name: param.name(),
+					kind: SpecializationParamKind::Value,
+					constraints: Vec::new(),
+					entry_point: Some(entry_point),
+				});
+				types.chain(values)
+			})
+		});
+
+		global.chain(entry_point_params).collect()
+	}
+
 	pub fn entry_point_count(&self) -> u32 {
 		rcall!(spReflection_getEntryPointCount(self)) as _
 	}
@@ -153,4 +206,9 @@ impl Shader {
 	pub fn global_params_var_layout(&self) -> Option<&VariableLayout> {
 		rcall!(spReflection_getGlobalParamsVarLayout(self) as Option<&VariableLayout>)
 	}
+
+	/// Produces the same reflection JSON as `slangc -reflection-json`.
+	pub fn to_json(&self) -> String {
+		super::json::program(self)
+	}
 }
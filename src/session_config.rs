@@ -0,0 +1,202 @@
+use std::ffi::CString;
+use std::path::PathBuf;
+
+use crate::{
+	CompileTarget, CompilerOptions, Error, FloatingPointMode, GlobalSession, LineDirectiveMode, MacroSet,
+	MatrixLayoutMode, Result, SLANG_E_INVALID_ARG, SLANG_FAIL, Session, SessionDesc, SourceLanguage, TargetDesc,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An owned description of a compilation target, see [`TargetDesc`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TargetConfig {
+	pub format: CompileTarget,
+	/// The profile name, e.g. `glsl_450` or `sm_6_5`.
+	pub profile: Option<String>,
+	/// A combination of the `SLANG_TARGET_FLAG_*` bits.
+	pub flags: u32,
+	pub floating_point_mode: Option<FloatingPointMode>,
+	pub line_directive_mode: Option<LineDirectiveMode>,
+	pub force_glsl_scalar_buffer_layout: bool,
+	pub options: CompilerOptions,
+}
+
+impl TargetConfig {
+	pub fn new(format: CompileTarget) -> Self {
+		Self {
+			format,
+			profile: None,
+			flags: 0,
+			floating_point_mode: None,
+			line_directive_mode: None,
+			force_glsl_scalar_buffer_layout: false,
+			options: CompilerOptions::default(),
+		}
+	}
+
+	pub fn profile(mut self, profile: &str) -> Self {
+		self.profile = Some(profile.to_owned());
+		self
+	}
+
+	pub fn flags(mut self, flags: u32) -> Self {
+		self.flags = flags;
+		self
+	}
+
+	pub fn floating_point_mode(mut self, mode: FloatingPointMode) -> Self {
+		self.floating_point_mode = Some(mode);
+		self
+	}
+
+	pub fn line_directive_mode(mut self, mode: LineDirectiveMode) -> Self {
+		self.line_directive_mode = Some(mode);
+		self
+	}
+
+	pub fn force_glsl_scalar_buffer_layout(mut self, enable: bool) -> Self {
+		self.force_glsl_scalar_buffer_layout = enable;
+		self
+	}
+
+	pub fn options(mut self, options: CompilerOptions) -> Self {
+		self.options = options;
+		self
+	}
+}
+
+/// An owned description of a session, see [`SessionDesc`].
+///
+/// Unlike [`SessionDesc`] it doesn't borrow anything, so it can be stored, cloned and sent to other threads, and
+/// creates a [`Session`] on demand.
+///
+/// ```ignore
+/// let config = slang::SessionConfig::new()
+///     .target(slang::TargetConfig::new(slang::CompileTarget::Spirv).profile("glsl_450"))
+///     .search_path("shaders")
+///     .macro_define("QUALITY", "2");
+/// let session = config.create_session(&global_session)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionConfig {
+	pub targets: Vec<TargetConfig>,
+	pub search_paths: Vec<PathBuf>,
+	pub macros: MacroSet,
+	pub default_matrix_layout: Option<MatrixLayoutMode>,
+	pub options: CompilerOptions,
+	/// Language preludes, see [`GlobalSession::set_language_prelude`]. Preludes belong to the global session, so they
+	/// are set on it by [`create_session`](Self::create_session) or [`apply_preludes`](Self::apply_preludes) and stay
+	/// in effect for its other sessions.
+	pub preludes: Vec<(SourceLanguage, String)>,
+}
+
+impl SessionConfig {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn target(mut self, target: TargetConfig) -> Self {
+		self.targets.push(target);
+		self
+	}
+
+	pub fn search_path(mut self, path: impl Into<PathBuf>) -> Self {
+		self.search_paths.push(path.into());
+		self
+	}
+
+	pub fn macro_define(mut self, name: &str, value: &str) -> Self {
+		self.macros.set(name, value);
+		self
+	}
+
+	pub fn macros(mut self, macros: MacroSet) -> Self {
+		self.macros = macros;
+		self
+	}
+
+	pub fn default_matrix_layout(mut self, mode: MatrixLayoutMode) -> Self {
+		self.default_matrix_layout = Some(mode);
+		self
+	}
+
+	pub fn options(mut self, options: CompilerOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Sets the prelude for `language`, replacing any previous one.
+	pub fn prelude(mut self, language: SourceLanguage, prelude: &str) -> Self {
+		self.preludes.retain(|(l, _)| *l != language);
+		self.preludes.push((language, prelude.to_owned()));
+		self
+	}
+
+	pub fn create_session(&self, global_session: &GlobalSession) -> Result<Session> {
+		// The preludes are only set once the rest of the description is known to be valid
+		self.with_session_desc(global_session, |desc| {
+			self.apply_preludes(global_session)?;
+			global_session.create_session(&desc).ok_or(Error::Code(SLANG_FAIL))
+		})?
+	}
+
+	/// Sets the preludes on `global_session`, e.g. before creating a session through
+	/// [`with_session_desc`](Self::with_session_desc). Stops at the first prelude containing a NUL character, leaving
+	/// the earlier ones set.
+	pub fn apply_preludes(&self, global_session: &GlobalSession) -> Result<()> {
+		for (language, prelude) in &self.preludes {
+			global_session.set_language_prelude(*language, prelude)?;
+		}
+		Ok(())
+	}
+
+	/// Calls `f` with the equivalent [`SessionDesc`], e.g. to add a file system before creating a session.
+	///
+	/// Fails if a search path or macro contains a NUL character. The global session is left unchanged, so the preludes
+	/// have to be applied separately with [`apply_preludes`](Self::apply_preludes).
+	pub fn with_session_desc<R>(&self, global_session: &GlobalSession, f: impl FnOnce(SessionDesc) -> R) -> Result<R> {
+		let c_string = |s: &str| CString::new(s).map_err(|_| Error::Code(SLANG_E_INVALID_ARG));
+
+		let targets = self.targets.iter().map(|target| {
+			let mut desc = TargetDesc::default()
+				.format(target.format)
+				.flags(target.flags)
+				.force_glsl_scalar_buffer_layout(target.force_glsl_scalar_buffer_layout)
+				.options(&target.options);
+			if let Some(profile) = &target.profile {
+				desc = desc.profile(global_session.find_profile(profile));
+			}
+			if let Some(mode) = target.floating_point_mode {
+				desc = desc.floating_point_mode(mode);
+			}
+			if let Some(mode) = target.line_directive_mode {
+				desc = desc.line_directive_mode(mode);
+			}
+			desc
+		}).collect::<Vec<_>>();
+
+		let search_paths_storage = self.search_paths.iter()
+			.map(|path| c_string(&path.to_string_lossy()))
+			.collect::<Result<Vec<_>>>()?;
+		let search_paths = search_paths_storage.iter().map(|path| path.as_ptr()).collect::<Vec<_>>();
+
+		if !self.macros.is_valid() {
+			return Err(Error::Code(SLANG_E_INVALID_ARG));
+		}
+
+		let mut desc = SessionDesc::default()
+			.targets(&targets)
+			.search_paths(&search_paths)
+			.macros(&self.macros)
+			.options(&self.options);
+		if let Some(mode) = self.default_matrix_layout {
+			desc = desc.default_matrix_layout_mode(mode);
+		}
+
+		Ok(f(desc))
+	}
+}
@@ -0,0 +1,269 @@
+//! A persistent cache of compiled entry points.
+//!
+//! Entries are keyed by the [session description digest](crate::GlobalSession::session_desc_digest) and the
+//! [entry point hash](crate::ComponentType::entry_point_hash), which *Slang* computes without generating any code. A
+//! hit therefore skips everything after parsing and type checking.
+//!
+//! Every entry is a single file that is written to a temporary name and then renamed into place, so several processes
+//! can share one cache directory: readers either see a complete entry or none at all.
+
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{ComponentType, Diagnostics, GlobalSession, Result, SessionDesc};
+
+const MAGIC: &[u8; 4] = b"SLCC";
+const VERSION: u32 = 1;
+const ENTRY_EXTENSION: &str = "bin";
+const TEMP_EXTENSION: &str = "tmp";
+/// Temporary files older than this were left behind by a process that died while writing.
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Identifies one compiled entry point in a [`ShaderCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey(u128);
+
+impl CacheKey {
+	/// The key for entry point `index` of `program` compiled for target `target` of the session created from
+	/// `session_desc`.
+	pub fn new(
+		global_session: &GlobalSession,
+		session_desc: &SessionDesc,
+		program: &ComponentType,
+		index: i64,
+		target: i64,
+	) -> Result<Self> {
+		let digest = global_session.session_desc_digest(session_desc)?;
+		let hash = program.entry_point_hash(index, target)?;
+		Ok(Self::from_parts(&[digest.as_slice(), hash.as_slice(), &target.to_le_bytes()]))
+	}
+
+	/// A key for arbitrary data, e.g. to cache code compiled in some other way.
+	pub fn from_parts(parts: &[&[u8]]) -> Self {
+		Self(crate::hash::fnv1a_128(parts.iter().copied()))
+	}
+
+	fn file_name(&self) -> String {
+		let mut name = String::with_capacity(36);
+		write!(name, "{:032x}.{ENTRY_EXTENSION}", self.0).unwrap();
+		name
+	}
+}
+
+/// Compiled code and the warnings reported while compiling it.
+#[derive(Debug, Clone)]
+pub struct CachedCode {
+	pub code: Vec<u8>,
+	pub diagnostics: Diagnostics,
+}
+
+/// A directory of compiled entry points with an optional size limit.
+pub struct ShaderCache {
+	dir: PathBuf,
+	max_size: Option<u64>,
+}
+
+impl ShaderCache {
+	/// Opens the cache in `dir`, creating the directory if needed.
+	pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+		let dir = dir.into();
+		std::fs::create_dir_all(&dir)?;
+		Ok(Self { dir, max_size: None })
+	}
+
+	/// Evicts the least recently used entries whenever the cache grows beyond `bytes`.
+	pub fn max_size(mut self, bytes: u64) -> Self {
+		self.max_size = Some(bytes);
+		self
+	}
+
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+
+	pub fn get(&self, key: &CacheKey) -> Option<CachedCode> {
+		let path = self.dir.join(key.file_name());
+		let entry = decode(&std::fs::read(&path).ok()?)?;
+
+		// Refresh the modification time, which eviction uses as the last access time
+		if let Ok(file) = std::fs::OpenOptions::new().append(true).open(&path) {
+			let _ = file.set_modified(access_time());
+		}
+
+		Some(entry)
+	}
+
+	pub fn insert(&self, key: &CacheKey, code: &[u8], diagnostics: &Diagnostics) -> io::Result<()> {
+		static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+		let temp_path = self.dir.join(format!(
+			"{:032x}.{}.{}.{TEMP_EXTENSION}",
+			key.0,
+			std::process::id(),
+			TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+		));
+
+		let result = (|| {
+			let mut file = std::fs::File::create(&temp_path)?;
+			file.write_all(&encode(code, diagnostics))?;
+			file.set_modified(access_time())?;
+			file.sync_all()?;
+			drop(file);
+			// Replaces an entry another process may have written in the meantime, which holds the same code
+			std::fs::rename(&temp_path, self.dir.join(key.file_name()))
+		})();
+		if result.is_err() {
+			let _ = std::fs::remove_file(&temp_path);
+		}
+		result?;
+
+		if let Some(max_size) = self.max_size {
+			self.evict_to(max_size, Some(key))?;
+		}
+		Ok(())
+	}
+
+	/// Returns the code of an entry point from the cache, or generates and stores it on a miss.
+	pub fn entry_point_code(
+		&self,
+		global_session: &GlobalSession,
+		session_desc: &SessionDesc,
+		program: &ComponentType,
+		index: i64,
+		target: i64,
+	) -> Result<CachedCode> {
+		let key = CacheKey::new(global_session, session_desc, program, index, target)?;
+		if let Some(entry) = self.get(&key) {
+			return Ok(entry);
+		}
+
+		let (code, diagnostics) = program.entry_point_code_with_diagnostics(index, target)?;
+		let entry = CachedCode { code: code.as_slice().to_owned(), diagnostics };
+		// A cache that can't be written to only costs performance
+		let _ = self.insert(&key, &entry.code, &entry.diagnostics);
+		Ok(entry)
+	}
+
+	pub fn remove(&self, key: &CacheKey) -> io::Result<()> {
+		ignore_not_found(std::fs::remove_file(self.dir.join(key.file_name())))
+	}
+
+	/// The total size of all entries in bytes.
+	pub fn size(&self) -> io::Result<u64> {
+		Ok(self.entries()?.iter().map(|entry| entry.size).sum())
+	}
+
+	/// Removes the least recently used entries until the cache fits into its size limit.
+	pub fn evict(&self) -> io::Result<()> {
+		match self.max_size {
+			Some(max_size) => self.evict_to(max_size, None),
+			None => Ok(()),
+		}
+	}
+
+	/// Removes all entries.
+	pub fn clear(&self) -> io::Result<()> {
+		self.evict_to(0, None)
+	}
+
+	fn evict_to(&self, max_size: u64, keep: Option<&CacheKey>) -> io::Result<()> {
+		let keep = keep.map(CacheKey::file_name);
+		let mut entries = self.entries()?;
+		let mut size = entries.iter().map(|entry| entry.size).sum::<u64>();
+
+		entries.sort_by(|a, b| (a.modified, &a.path).cmp(&(b.modified, &b.path)));
+		for entry in entries {
+			if size <= max_size {
+				break;
+			}
+			if keep.as_deref().is_some_and(|keep| entry.path.ends_with(keep)) {
+				continue;
+			}
+			// Another process may be evicting the same entry
+			ignore_not_found(std::fs::remove_file(&entry.path))?;
+			size -= entry.size;
+		}
+
+		Ok(())
+	}
+
+	/// Lists all entries and removes stale temporary files on the way.
+	fn entries(&self) -> io::Result<Vec<Entry>> {
+		let mut entries = Vec::new();
+		let now = SystemTime::now();
+
+		for dir_entry in std::fs::read_dir(&self.dir)? {
+			let dir_entry = dir_entry?;
+			let path = dir_entry.path();
+			let Ok(metadata) = dir_entry.metadata() else { continue };
+			let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+
+			match path.extension().and_then(|e| e.to_str()) {
+				Some(ENTRY_EXTENSION) => entries.push(Entry { path, size: metadata.len(), modified }),
+				Some(TEMP_EXTENSION) if now.duration_since(modified).is_ok_and(|age| age > STALE_TEMP_AGE) => {
+					let _ = std::fs::remove_file(&path);
+				}
+				_ => {}
+			}
+		}
+
+		Ok(entries)
+	}
+}
+
+struct Entry {
+	path: PathBuf,
+	size: u64,
+	modified: SystemTime,
+}
+
+/// The current time, but always later than the previous access of this process, so that eviction keeps the order of
+/// accesses that happen within the resolution of the clock. Microseconds are kept by all common file systems.
+fn access_time() -> SystemTime {
+	static LAST_ACCESS: AtomicU64 = AtomicU64::new(0);
+
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+	let last = LAST_ACCESS
+		.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| Some(now.max(last + 1)))
+		.unwrap_or_else(|last| last);
+	UNIX_EPOCH + Duration::from_micros(now.max(last + 1))
+}
+
+fn ignore_not_found(result: io::Result<()>) -> io::Result<()> {
+	match result {
+		Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+		result => result,
+	}
+}
+
+/// `MAGIC`, `VERSION`, the code length and the code, followed by the diagnostics text.
+fn encode(code: &[u8], diagnostics: &Diagnostics) -> Vec<u8> {
+	let text = diagnostics.text().as_bytes();
+	let mut data = Vec::with_capacity(16 + code.len() + text.len());
+	data.extend_from_slice(MAGIC);
+	data.extend_from_slice(&VERSION.to_le_bytes());
+	data.extend_from_slice(&(code.len() as u64).to_le_bytes());
+	data.extend_from_slice(code);
+	data.extend_from_slice(text);
+	data
+}
+
+fn decode(data: &[u8]) -> Option<CachedCode> {
+	let (magic, data) = data.split_first_chunk::<4>()?;
+	let (version, data) = data.split_first_chunk::<4>()?;
+	let (code_len, data) = data.split_first_chunk::<8>()?;
+	if magic != MAGIC || u32::from_le_bytes(*version) != VERSION {
+		return None;
+	}
+
+	let code_len = usize::try_from(u64::from_le_bytes(*code_len)).ok()?;
+	let (code, text) = (data.get(..code_len)?, data.get(code_len..)?);
+
+	Some(CachedCode {
+		code: code.to_owned(),
+		diagnostics: Diagnostics::parse(std::str::from_utf8(text).ok()?),
+	})
+}
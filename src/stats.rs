@@ -0,0 +1,163 @@
+//! Time spent compiling, as reported by *Slang* and as measured by this crate around its calls.
+//!
+//! ```ignore
+//! let before = global_session.elapsed_time();
+//! let (program, timings) = slang::measure_operations(|| compile(&session));
+//! let stats = global_session.elapsed_time().since(&before);
+//! println!("{:?} in link, {:?} downstream", timings.link.total, stats.downstream);
+//! ```
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use crate::{GlobalSession, Interface, vcall};
+
+/// Time spent compiling by all sessions of a global session, see [`GlobalSession::elapsed_time`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CompileStats {
+	/// Everything, including the downstream compilers.
+	pub total: Duration,
+	/// Only the downstream compilers, e.g. *dxc* or *glslang*.
+	pub downstream: Duration,
+}
+
+impl CompileStats {
+	/// The time spent in *Slang* itself.
+	pub fn frontend(&self) -> Duration {
+		self.total.saturating_sub(self.downstream)
+	}
+
+	/// The time spent since `earlier` was taken.
+	pub fn since(&self, earlier: &CompileStats) -> CompileStats {
+		CompileStats {
+			total: self.total.saturating_sub(earlier.total),
+			downstream: self.downstream.saturating_sub(earlier.downstream),
+		}
+	}
+}
+
+impl GlobalSession {
+	/// The time spent compiling since the global session was created.
+	pub fn elapsed_time(&self) -> CompileStats {
+		let mut total = 0.0;
+		let mut downstream = 0.0;
+		vcall!(self, getCompilerElapsedTime(&mut total, &mut downstream));
+		CompileStats {
+			total: Duration::try_from_secs_f64(total).unwrap_or_default(),
+			downstream: Duration::try_from_secs_f64(downstream).unwrap_or_default(),
+		}
+	}
+}
+
+/// How often and how long one kind of operation ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OperationStats {
+	pub count: u32,
+	pub total: Duration,
+	/// The longest single call.
+	pub max: Duration,
+}
+
+impl OperationStats {
+	fn record(&mut self, elapsed: Duration) {
+		self.count += 1;
+		self.total += elapsed;
+		self.max = self.max.max(elapsed);
+	}
+
+	fn merge(&mut self, other: &OperationStats) {
+		self.count += other.count;
+		self.total += other.total;
+		self.max = self.max.max(other.max);
+	}
+}
+
+/// The time spent in each kind of operation, see [`measure_operations`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OperationTimings {
+	/// All ways of loading a module, e.g. [`Session::load_module`](crate::Session::load_module).
+	pub load_module: OperationStats,
+	/// [`ComponentType::link`](crate::ComponentType::link) and its variants.
+	pub link: OperationStats,
+	/// [`ComponentType::target_code`](crate::ComponentType::target_code),
+	/// [`ComponentType::entry_point_code`](crate::ComponentType::entry_point_code) and
+	/// [`ComponentType::entry_point_host_callable`](crate::ComponentType::entry_point_host_callable), including
+	/// downstream compilers.
+	pub target_code: OperationStats,
+}
+
+impl OperationTimings {
+	pub fn total(&self) -> Duration {
+		self.load_module.total + self.link.total + self.target_code.total
+	}
+
+	fn merge(&mut self, other: &OperationTimings) {
+		self.load_module.merge(&other.load_module);
+		self.link.merge(&other.link);
+		self.target_code.merge(&other.target_code);
+	}
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Operation {
+	LoadModule,
+	Link,
+	TargetCode,
+}
+
+thread_local! {
+	static TIMINGS: Cell<Option<OperationTimings>> = const { Cell::new(None) };
+}
+
+/// Calls `f` and returns the time spent in the operations this crate performed on the current thread meanwhile.
+/// Nested measurements also count towards the enclosing one.
+pub fn measure_operations<R>(f: impl FnOnce() -> R) -> (R, OperationTimings) {
+	let measurement = Measurement { outer: TIMINGS.replace(Some(OperationTimings::default())) };
+	let result = f();
+	let timings = measurement.finish();
+	(result, timings)
+}
+
+/// Restores the enclosing measurement when dropped, so that a panic in a measured closure doesn't leave its
+/// measurement active on the thread.
+struct Measurement {
+	outer: Option<OperationTimings>,
+}
+
+impl Measurement {
+	fn finish(self) -> OperationTimings {
+		TIMINGS.get().unwrap_or_default()
+	}
+}
+
+impl Drop for Measurement {
+	fn drop(&mut self) {
+		let timings = TIMINGS.replace(self.outer).unwrap_or_default();
+		if let Some(mut outer) = self.outer {
+			outer.merge(&timings);
+			TIMINGS.set(Some(outer));
+		}
+	}
+}
+
+/// Runs `f` and records its duration as `operation` if a measurement is active, see [`measure_operations`].
+pub(crate) fn timed<R>(operation: Operation, f: impl FnOnce() -> R) -> R {
+	if TIMINGS.get().is_none() {
+		return f();
+	}
+
+	let start = Instant::now();
+	let result = f();
+	let elapsed = start.elapsed();
+
+	if let Some(mut timings) = TIMINGS.get() {
+		match operation {
+			Operation::LoadModule => timings.load_module.record(elapsed),
+			Operation::Link => timings.link.record(elapsed),
+			Operation::TargetCode => timings.target_code.record(elapsed),
+		}
+		TIMINGS.set(Some(timings));
+	}
+
+	result
+}
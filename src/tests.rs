@@ -144,3 +144,1020 @@ fn com_impls_blob()
 	assert_eq!(original_ir_bytes, recreated_ir_bytes, "The IR blobs should be identical");
 	assert_eq!(original_bytecode, recreated_bytecode, "The compiled programs should be identical");
 }
+
+#[cfg(feature="com_impls")]
+#[test]
+fn com_impls_file_system()
+{
+	// Serve the test shader from memory under a directory that does not exist on disk
+	let file_system = slang::ComPtr::new(slang::BoxedFileSystem::new(
+		slang::MemoryFileSystem::new().with_file("virtual/test.slang", include_str!("../shaders/test.slang"))
+	));
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let target_desc = slang::TargetDesc::default()
+		.format(slang::CompileTarget::Spirv)
+		.profile(global_session.find_profile("glsl_450"));
+	let targets = [target_desc];
+	let search_path = std::ffi::CString::new("./virtual").unwrap();
+	let search_paths = [search_path.as_ptr()];
+
+	let session_desc = slang::SessionDesc::default()
+		.targets(&targets)
+		.search_paths(&search_paths)
+		.file_system(&file_system);
+	let session = global_session.create_session(&session_desc).unwrap();
+
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session
+		.create_composite_component_type(&[module.into(), entry_point.into()])
+		.unwrap();
+	let linked_program = program.link().unwrap();
+
+	assert_ne!(linked_program.entry_point_code(0, 0).unwrap().as_slice().len(), 0);
+	assert_eq!(slang::normalize_path("./virtual\\sub/../test.slang"), "virtual/test.slang");
+}
+
+#[test]
+fn diagnostics_parse()
+{
+	let diagnostics = slang::Diagnostics::parse(concat!(
+		"shaders/broken.slang(4): error 30015: undefined identifier 'undefinedVariable'.\n",
+		"\treturn undefinedVariable;\n",
+		"\t       ^~~~~~~~~~~~~~~~~\n",
+		"shaders/broken.slang(2, 7): warning 30081: implicit conversion from 'int' to 'float'\n",
+		"fatal error: unable to continue\n",
+	));
+
+	assert_eq!(diagnostics.len(), 3);
+	assert!(diagnostics.has_errors());
+	assert_eq!(diagnostics.warnings().count(), 1);
+
+	let error = &diagnostics[0];
+	assert_eq!(error.severity, slang::Severity::Error);
+	assert_eq!(error.code, Some(30015));
+	assert_eq!(error.code_string().as_deref(), Some("E30015"));
+	assert_eq!(error.path.as_deref(), Some("shaders/broken.slang"));
+	assert_eq!(error.span, Some(slang::Span { line: 4, column: Some(9), end_column: Some(26) }));
+	assert_eq!(error.message, "undefined identifier 'undefinedVariable'.");
+
+	assert_eq!(diagnostics[1].span, Some(slang::Span { line: 2, column: Some(7), end_column: None }));
+	assert_eq!(diagnostics[2].severity, slang::Severity::Fatal);
+	assert_eq!(diagnostics[2].path, None);
+}
+
+#[test]
+fn diagnostics_from_failed_load()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+
+	let Err(error) = session.load_module_from_source_string(
+		"broken", "broken.slang", "float broken() { return undefinedVariable; }"
+	) else {
+		panic!("loading a broken module should fail");
+	};
+	let diagnostics = error.diagnostics().expect("Slang should report why the module failed to load");
+	let undefined = diagnostics.errors().next().unwrap();
+	assert_eq!(undefined.code, Some(30015));
+	assert_eq!(undefined.path.as_deref(), Some("broken.slang"));
+	assert_eq!(undefined.span.map(|span| span.line), Some(1));
+}
+
+#[test]
+fn specialize()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+
+	let module = session.load_module("specialize.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session
+		.create_composite_component_type(&[module.into(), entry_point.into()])
+		.unwrap();
+	assert_eq!(program.specialization_param_count(), 1);
+
+	let reflection = program.layout(0).unwrap();
+	let params = reflection.specialization_params();
+	assert_eq!(params.len(), 1);
+	assert_eq!(params[0].name, Some("TMaterial"));
+	assert_eq!(params[0].kind, slang::reflection::SpecializationParamKind::Type);
+	assert!(params[0].entry_point.is_some());
+
+	// Arguments can be given either as reflected types or as Slang expressions
+	let doubling = reflection.find_type_by_name("Doubling").unwrap();
+	let by_type = program.specialize(&[slang::SpecializationArg::Type(doubling)]).unwrap();
+	let by_expr = program.specialize(&[slang::SpecializationArg::Expr("Halving")]).unwrap();
+
+	for specialized in [by_type, by_expr] {
+		assert_eq!(specialized.specialization_param_count(), 0);
+		let linked = specialized.link().unwrap();
+		assert_ne!(linked.entry_point_code(0, 0).unwrap().as_slice().len(), 0);
+	}
+}
+
+#[test]
+fn type_conformance()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+
+	let module = session.load_module("specialize.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session
+		.create_composite_component_type(&[module.clone().into(), entry_point.clone().into()])
+		.unwrap();
+	let reflection = program.layout(0).unwrap();
+
+	let interface = reflection.find_type_by_name("IMaterial").unwrap();
+	let doubling = reflection.find_type_by_name("Doubling").unwrap();
+	let halving = reflection.find_type_by_name("Halving").unwrap();
+
+	let doubling_conformance = session.create_type_conformance(doubling, interface, Some(7)).unwrap();
+	let halving_conformance = session.create_type_conformance(halving, interface, None).unwrap();
+	assert_ne!(
+		session.type_conformance_witness_sequential_id(doubling, interface).unwrap(),
+		session.type_conformance_witness_sequential_id(halving, interface).unwrap()
+	);
+
+	assert!(!session.type_conformance_witness_mangled_name(doubling, interface).unwrap().is_empty());
+	assert!(!session.type_rtti_mangled_name(doubling).unwrap().is_empty());
+
+	// The conformances take part in linking like any other component
+	let program = session
+		.create_composite_component_type(&[
+			module.into(), entry_point.into(), doubling_conformance.into(), halving_conformance.into()
+		])
+		.unwrap();
+	let specialized = program.specialize(&[slang::SpecializationArg::Type(doubling)]).unwrap();
+	assert_ne!(specialized.link().unwrap().entry_point_code(0, 0).unwrap().as_slice().len(), 0);
+}
+
+#[test]
+fn host_callable()
+{
+	// Mirrors the layout of the shader parameters on the host target
+	#[repr(C)]
+	struct EntryPointParams { factor: f32 }
+	#[repr(C)]
+	struct GlobalParams { output: *mut f32, output_count: usize }
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let targets = [slang::TargetDesc::default().format(slang::CompileTarget::ShaderHostCallable)];
+	let search_path = std::ffi::CString::new("shaders").unwrap();
+	let search_paths = [search_path.as_ptr()];
+	let session_desc = slang::SessionDesc::default()
+		.targets(&targets)
+		.search_paths(&search_paths);
+	let session = global_session.create_session(&session_desc).unwrap();
+
+	let module = session.load_module("host_callable.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("scale").unwrap();
+	let program = session
+		.create_composite_component_type(&[module.into(), entry_point.into()])
+		.unwrap();
+	let library = program.link().unwrap().entry_point_host_callable(0, 0).unwrap();
+
+	let mut data = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+	let mut entry_point_params = EntryPointParams { factor: 2.0 };
+	let mut global_params = GlobalParams { output: data.as_mut_ptr(), output_count: data.len() };
+	unsafe {
+		library.dispatch_compute("scale", [2, 1, 1], &mut entry_point_params, &mut global_params).unwrap();
+	}
+	assert_eq!(data, [2.0, 4.0, 6.0, 8.0, 10.0, 12.0, 14.0, 16.0]);
+}
+
+#[test]
+fn compile_pool()
+{
+	fn assert_send<T: Send>() {}
+	assert_send::<slang::CompileJob>();
+	assert_send::<slang::Result<slang::CompiledShader>>();
+
+	let pool = slang::CompilePool::new(2);
+	let jobs = (0..4).map(|_| {
+		slang::CompileJob::new("test", include_str!("../shaders/test.slang"), "main", slang::CompileTarget::Spirv)
+			.profile("glsl_450")
+			.options(slang::CompilerOptions::default().optimization(slang::OptimizationLevel::High))
+	});
+
+	let results = pool.compile_all(jobs);
+	assert_eq!(results.len(), 4);
+	for result in results {
+		let shader = result.unwrap();
+		assert_ne!(shader.code.len(), 0);
+		assert_eq!(shader.reflection.entry_points[0].stage, slang::Stage::Compute);
+		assert_eq!(shader.reflection.entry_points[0].thread_group_size, [1, 1, 1]);
+		assert_eq!(shader.reflection.parameters.len(), 3);
+	}
+
+	// Errors are reported per job and leave the workers usable
+	let broken = slang::CompileJob::new("broken", "void main() { undefined(); }", "main", slang::CompileTarget::Spirv);
+	assert!(pool.submit(broken).wait().unwrap_err().diagnostics().is_some_and(|d| d.has_errors()));
+}
+
+#[test]
+fn compiler_options_clone()
+{
+	let original = slang::CompilerOptions::default()
+		.macro_define("A", "1")
+		.optimization(slang::OptimizationLevel::High)
+		.include("shaders");
+	let clone = original.clone();
+	drop(original);
+
+	// The cloned entries must point into the clone's own strings rather than into the dropped original
+	let value = |index: usize, string: usize| {
+		let value = &clone.options[index].value;
+		let ptr = if string == 0 { value.stringValue0 } else { value.stringValue1 };
+		unsafe { std::ffi::CStr::from_ptr(ptr) }
+	};
+	assert_eq!(clone.options.len(), 3);
+	assert_eq!(value(0, 0).as_ptr(), clone.strings[0].as_ptr());
+	assert_eq!(value(0, 1).as_ptr(), clone.strings[1].as_ptr());
+	assert_eq!(value(2, 0).as_ptr(), clone.strings[2].as_ptr());
+	assert_eq!(value(2, 0).to_str().unwrap(), "shaders");
+}
+
+#[test]
+fn program_reflection()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = session
+		.create_composite_component_type(&[module.into(), entry_point.into()])
+		.unwrap()
+		.link()
+		.unwrap();
+
+	// The snapshot stays usable after all Slang objects are gone
+	let reflection = slang::reflection::ProgramReflection::new(linked_program.layout(0).unwrap());
+	drop((linked_program, session, global_session));
+
+	let names = reflection.parameters.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+	assert_eq!(names, ["input_0", "input_1", "output"]);
+
+	let output = reflection.find_parameter_by_name("output").unwrap();
+	assert_eq!(output.type_layout.kind, slang::TypeKind::Resource);
+	assert_eq!(output.type_layout.resource_access, Some(slang::ResourceAccess::ReadWrite));
+	assert_eq!(output.type_layout.element.as_ref().unwrap().scalar_type, Some(slang::ScalarType::Float32));
+	assert!(!output.bindings.is_empty());
+
+	let main = reflection.find_entry_point_by_name("main").unwrap();
+	assert_eq!(main.stage, slang::Stage::Compute);
+	assert_eq!(main.thread_group_size, [1, 1, 1]);
+
+	#[cfg(feature="serde")] {
+		fn assert_serde<T: serde::Serialize + for<'de> serde::Deserialize<'de>>(_: &T) {}
+		assert_serde(&reflection);
+	}
+}
+
+#[test]
+fn reflection_json()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = session
+		.create_composite_component_type(&[module.into(), entry_point.into()])
+		.unwrap()
+		.link()
+		.unwrap();
+
+	let json = linked_program.layout(0).unwrap().to_json();
+	assert!(json.starts_with("{\n    \"parameters\": [\n"));
+	assert!(json.contains("\"name\": \"output\""));
+	assert!(json.contains("\"baseShape\": \"structuredBuffer\""));
+	assert!(json.contains("\"access\": \"readWrite\""));
+	assert!(json.contains("\"scalarType\": \"float32\""));
+	assert!(json.contains("\"stage\": \"compute\""));
+	assert!(json.contains("\"threadGroupSize\": [1, 1, 1]"));
+}
+
+#[test]
+fn codegen_structs()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session.load_module("codegen.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session
+		.create_composite_component_type(&[module.into(), entry_point.into()])
+		.unwrap();
+	let reflection = program.layout(0).unwrap();
+
+	let params = reflection.parameters().find(|p| p.name() == Some("params")).unwrap();
+	let mut structs = slang::codegen::StructWriter::new();
+	assert_eq!(structs.add(params.type_layout().unwrap()), "Params");
+	let code = structs.finish();
+
+	// std140 pads the float3 only where the next member doesn't fit and each array element to 16 bytes
+	assert!(code.contains("#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]\npub struct Params {\n"));
+	assert!(code.contains("\tpub direction: [f32; 3],\n\tpub scale: f32,\n"));
+	assert!(code.contains("\tpub weights: [[f32; 4]; 3],\n"));
+	assert!(code.contains("\tpub light: Light,\n"));
+	assert!(code.contains("\tpub enabled: u32,\n"));
+	assert!(!code.contains("albedo"));
+	assert!(code.contains("const _: () = assert!(std::mem::offset_of!(Params, scale) == 12);"));
+	// Nested structs are defined before the structs containing them
+	assert!(code.find("pub struct Light").unwrap() < code.find("pub struct Params").unwrap());
+	// Padding doesn't reuse the names of shader fields
+	assert!(code.contains("pub struct Falloff {\n\tpub weights: [[f32; 4]; 2],\n\tpub _pad0: f32,\n\tpub _pad1: [u8; 12],\n}"));
+
+	// The same struct in a structured buffer is laid out without std140 padding and gets a definition of its own
+	let falloffs = reflection.parameters().find(|p| p.name() == Some("falloffs")).unwrap();
+	let mut structs = slang::codegen::StructWriter::new();
+	assert_eq!(structs.add(params.type_layout().unwrap()), "Params");
+	assert_eq!(structs.add(falloffs.type_layout().unwrap()), "FalloffLayout2");
+	assert_eq!(structs.add(falloffs.type_layout().unwrap()), "FalloffLayout2");
+	let code = structs.finish();
+	assert!(code.contains("pub struct FalloffLayout2 {\n\tpub weights: [f32; 2],\n\tpub _pad0: f32,\n}"));
+}
+
+#[cfg(feature="build")]
+#[test]
+fn build_shaders()
+{
+	let root = std::env::temp_dir().join(format!("slang-build-test-{}", std::process::id()));
+	let source_dir = root.join("src");
+	std::fs::create_dir_all(source_dir.join("nested")).unwrap();
+	std::fs::copy("shaders/test.slang", source_dir.join("nested/multiply.slang")).unwrap();
+	// A keyword can only be used as a module name as a raw identifier
+	std::fs::copy("shaders/test.slang", source_dir.join("type.slang")).unwrap();
+
+	let artifacts = slang::build::ShaderBuilder::new(&source_dir)
+		.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+		.target(slang::CompileTarget::Wgsl, None)
+		.out_dir(root.join("out"))
+		.build()
+		.unwrap();
+
+	assert_eq!(artifacts.len(), 4);
+	assert_eq!(artifacts[0].constant, "nested::multiply::MAIN_SPIRV");
+	assert_eq!(artifacts[1].constant, "nested::multiply::MAIN_WGSL");
+	assert_eq!(artifacts[2].constant, "r#type::MAIN_SPIRV");
+	assert!(artifacts[1].path.ends_with("shaders/nested.multiply.main.wgsl"));
+	assert!(artifacts.iter().all(|artifact| std::fs::metadata(&artifact.path).unwrap().len() > 0));
+
+	let rust_module = std::fs::read_to_string(root.join("out/shaders.rs")).unwrap();
+	assert!(rust_module.contains("pub mod nested {\n\tpub mod multiply {\n\t\tpub const MAIN_SPIRV: &[u8] = include_bytes!("));
+	assert!(rust_module.contains("pub mod r#type {\n\tpub const MAIN_SPIRV: &[u8] = include_bytes!("));
+
+	std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(feature="build")]
+#[test]
+fn build_shaders_profiles()
+{
+	let root = std::env::temp_dir().join(format!("slang-build-profiles-test-{}", std::process::id()));
+	let source_dir = root.join("src");
+	std::fs::create_dir_all(&source_dir).unwrap();
+	std::fs::copy("shaders/test.slang", source_dir.join("multiply.slang")).unwrap();
+
+	let builder = || slang::build::ShaderBuilder::new(&source_dir)
+		.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+		.target(slang::CompileTarget::Spirv, Some("spirv_1_5"))
+		.out_dir(root.join("out"));
+
+	// Targets of the same format are told apart by their profile
+	let artifacts = builder().build().unwrap();
+	assert_eq!(artifacts.len(), 2);
+	assert_eq!(artifacts[0].constant, "multiply::MAIN_SPIRV_GLSL_450");
+	assert_eq!(artifacts[1].constant, "multiply::MAIN_SPIRV_SPIRV_1_5");
+	assert!(artifacts[0].path.ends_with("shaders/multiply.main.spirv_glsl_450.spv"));
+	assert!(artifacts[1].path.ends_with("shaders/multiply.main.spirv_spirv_1_5.spv"));
+
+	// Both files would end up as `mod multi_ply`
+	std::fs::copy("shaders/test.slang", source_dir.join("multi-ply.slang")).unwrap();
+	std::fs::copy("shaders/test.slang", source_dir.join("multi_ply.slang")).unwrap();
+	let Err(slang::build::BuildError::Conflict(name, ..)) = builder().build() else {
+		panic!("conflicting constants were not detected");
+	};
+	assert_eq!(name, "multi_ply::MAIN_SPIRV_GLSL_450");
+
+	std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn shader_cache()
+{
+	let dir = std::env::temp_dir().join(format!("slang-cache-test-{}", std::process::id()));
+	let cache = slang::ShaderCache::new(&dir).unwrap();
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let search_paths = [c"shaders".as_ptr()];
+	let targets = [slang::TargetDesc::default().format(slang::CompileTarget::Spirv)];
+	let session_desc = slang::SessionDesc::default().targets(&targets).search_paths(&search_paths);
+	let session = global_session.create_session(&session_desc).unwrap();
+
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session.create_composite_component_type(&[module.into(), entry_point.into()]).unwrap();
+	let program = program.link().unwrap();
+
+	let key = slang::CacheKey::new(&global_session, &session_desc, &program, 0, 0).unwrap();
+	assert!(cache.get(&key).is_none());
+
+	let compiled = cache.entry_point_code(&global_session, &session_desc, &program, 0, 0).unwrap();
+	let cached = cache.get(&key).unwrap();
+	assert_eq!(cached.code, compiled.code);
+	assert_eq!(cached.code, program.entry_point_code(0, 0).unwrap().as_slice());
+
+	cache.clear().unwrap();
+	assert!(cache.get(&key).is_none());
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn shader_cache_eviction()
+{
+	let dir = std::env::temp_dir().join(format!("slang-cache-eviction-test-{}", std::process::id()));
+	let cache = slang::ShaderCache::new(&dir).unwrap().max_size(500);
+	let keys = [b"a", b"b", b"c", b"d"].map(|part| slang::CacheKey::from_parts(&[part]));
+	let diagnostics = slang::Diagnostics::parse("test.slang(1): warning 15205: unused\n");
+
+	// Each entry takes about 150 bytes, so three of them fit
+	for key in &keys[..3] {
+		cache.insert(key, &[0; 100], &diagnostics).unwrap();
+	}
+	assert!(cache.get(&keys[0]).is_some());
+	cache.insert(&keys[3], &[0; 100], &diagnostics).unwrap();
+
+	// The second entry is the least recently used one, as the first was read after it was inserted
+	assert!(cache.size().unwrap() <= 500);
+	let present = keys.map(|key| cache.get(&key).is_some());
+	assert_eq!(present, [true, false, true, true]);
+	let entry = cache.get(&keys[3]).unwrap();
+	assert_eq!(entry.code, [0; 100]);
+	assert_eq!(entry.diagnostics.text(), diagnostics.text());
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature="com_impls")]
+#[test]
+fn module_library()
+{
+	let dir = std::env::temp_dir().join(format!("slang-module-library-test-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let source_path = dir.join("multiply.slang");
+	std::fs::copy("shaders/test.slang", &source_path).unwrap();
+
+	let load = || {
+		// Every load uses a fresh session, like separate runs of an application would
+		let global_session = slang::GlobalSession::new().unwrap();
+		let session = obtain_test_session(&global_session, &[&dir]).unwrap();
+		let library = slang::ModuleLibrary::new(&session).binary_dir(dir.join("cache"));
+		let loaded = library.load(&source_path).unwrap();
+		assert!(loaded.module.find_entry_point_by_name("main").is_some());
+		assert_eq!(loaded.binary_path.parent(), Some(dir.join("cache").as_path()));
+		loaded.origin
+	};
+
+	assert_eq!(load(), slang::ModuleOrigin::Compiled);
+	assert_eq!(load(), slang::ModuleOrigin::Binary);
+
+	let mut source = std::fs::read_to_string(&source_path).unwrap();
+	source.push_str("\n// changed\n");
+	std::fs::write(&source_path, source).unwrap();
+	assert_eq!(load(), slang::ModuleOrigin::Recompiled);
+	assert_eq!(load(), slang::ModuleOrigin::Binary);
+
+	// Source files of the same name in different directories get their own binary modules
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &[&dir]).unwrap();
+	let library = slang::ModuleLibrary::new(&session).binary_dir(dir.join("cache"));
+	let other_path = library.binary_path(&dir.join("other/multiply.slang"));
+	assert_ne!(library.binary_path(&source_path), other_path);
+	assert!(other_path.file_name().unwrap().to_str().unwrap().starts_with("multiply."));
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn module_graph()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	session.load_module("graph_main").unwrap();
+
+	let names = session.loaded_modules().map(|m| m.name().to_owned()).collect::<Vec<_>>();
+	for name in ["graph_main", "graph_lighting", "graph_common"] {
+		assert!(names.iter().any(|n| n == name), "{name} not in {names:?}");
+	}
+
+	let graph = session.module_graph();
+	let main = graph.find_by_name("graph_main").unwrap();
+	let lighting = graph.find_by_name("graph_lighting").unwrap();
+	let common = graph.find_by_name("graph_common").unwrap();
+
+	let mut main_imports = graph.transitive_imports(main).to_vec();
+	main_imports.sort();
+	let mut expected = vec![lighting, common];
+	expected.sort();
+	assert_eq!(main_imports, expected);
+	assert_eq!(graph.transitive_imports(lighting), [common]);
+	assert!(graph.transitive_imports(common).is_empty());
+
+	// graph_main imports graph_common directly as well, but that edge is implied by graph_lighting
+	assert_eq!(graph.reduced_imports(main), [lighting]);
+	assert_eq!(graph.reduced_imports(lighting), [common]);
+	assert_eq!(graph.importers(common).collect::<Vec<_>>(), [lighting]);
+
+	let common_path = graph.modules()[common].file_path().to_owned();
+	let mut dependents = graph.dependents_of_file(&common_path).collect::<Vec<_>>();
+	dependents.sort();
+	let mut expected = vec![main, lighting, common];
+	expected.sort();
+	assert_eq!(dependents, expected);
+	assert!(graph.dependency_file_paths().contains(common_path.as_str()));
+}
+
+#[cfg(feature="hot_reload")]
+#[test]
+fn hot_reload()
+{
+	use slang::hot_reload::{ShaderWatcher, WatchedProgram};
+
+	let dir = std::env::temp_dir().join(format!("slang-hot-reload-test-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let source_path = dir.join("reloaded.slang");
+	let source = std::fs::read_to_string("shaders/test.slang").unwrap();
+	std::fs::write(&source_path, &source).unwrap();
+
+	let mut watcher = ShaderWatcher::new().unwrap();
+	let (id, program) = watcher.add(
+		WatchedProgram::new("reloaded")
+			.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+			.search_path(dir.to_str().unwrap())
+	).unwrap();
+	assert_eq!(program.entry_points, ["main"]);
+	assert!(!program.targets[0].code[0].is_empty());
+	assert!(watcher.poll().is_empty());
+
+	// Modification times may have a coarse resolution
+	std::thread::sleep(std::time::Duration::from_millis(1100));
+	std::fs::write(&source_path, source.replace("input_0[index] * input_1[index]", "syntax error")).unwrap();
+	let reloads = watcher.poll();
+	assert_eq!(reloads.len(), 1);
+	assert_eq!(reloads[0].id, id);
+	let error = reloads.into_iter().next().unwrap().result.err().unwrap();
+	assert!(error.diagnostics().unwrap().has_errors());
+	assert_eq!(watcher.program(id).targets[0].code, program.targets[0].code);
+
+	std::thread::sleep(std::time::Duration::from_millis(1100));
+	std::fs::write(&source_path, source.replace('*', "+")).unwrap();
+	let reloads = watcher.poll();
+	let reloaded = reloads.into_iter().next().unwrap().result.unwrap();
+	assert_ne!(reloaded.targets[0].code, program.targets[0].code);
+	assert_eq!(watcher.program(id).targets[0].code, reloaded.targets[0].code);
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn link_with_options()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session.create_composite_component_type(&[module.into(), entry_point.into()]).unwrap();
+
+	let optimized = program.link().unwrap().entry_point_code(0, 0).unwrap();
+
+	// The session enables optimizations, which the per-link options override for this program only
+	let options = slang::CompilerOptions::default()
+		.optimization(slang::OptimizationLevel::None)
+		.debug_information(slang::DebugInfoLevel::Maximal);
+	let (linked, _) = program.link_with_options_and_diagnostics(&options).unwrap();
+	let debug = linked.entry_point_code(0, 0).unwrap();
+
+	assert_ne!(optimized.as_slice(), debug.as_slice());
+	assert_eq!(program.link().unwrap().entry_point_code(0, 0).unwrap().as_slice(), optimized.as_slice());
+}
+
+#[test]
+fn compiler_options_entries()
+{
+	use slang::{CompilerOptionName, CompilerOptionValue};
+
+	let options = slang::CompilerOptions::default()
+		.vulkan_bind_shift(slang::VulkanShiftKind::UnorderedAccess, 1, 100)
+		.downstream_args("dxc", "-Zi\n-Qembed_debug")
+		.denormal_mode_fp32(slang::FpDenormalMode::FlushToZero)
+		.raw(CompilerOptionName::DumpIntermediatePrefix, CompilerOptionValue::String("dump/".into()))
+		.unwrap();
+
+	assert_eq!(options.entries().len(), 4);
+	assert_eq!(options.entries()[0].value, CompilerOptionValue::Int((2 << 24) | 1, 100));
+	assert_eq!(options.entries()[2].value, CompilerOptionValue::Int(2, 0));
+	assert_eq!(options.clone(), options);
+	assert!(format!("{options:?}").contains("DownstreamArgs"));
+
+	// Values of the wrong kind and strings that can't be passed to C are rejected
+	let options = slang::CompilerOptions::default();
+	assert!(options.clone().raw(CompilerOptionName::Include, CompilerOptionValue::Int(1, 0)).is_err());
+	assert!(options.clone().raw(CompilerOptionName::Optimization, CompilerOptionValue::String("2".into())).is_err());
+	assert!(options.clone().raw(CompilerOptionName::Include, CompilerOptionValue::String("a\0b".into())).is_err());
+	assert!(options.raw(CompilerOptionName::CountOf, CompilerOptionValue::Int(0, 0)).is_err());
+}
+
+#[test]
+fn parse_command_line()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let parsed = global_session.parse_command_line(&[
+		"-target", "spirv", "-profile", "glsl_450", "-O3", "-DQUALITY=2", "-I", "shaders", "-matrix-layout-row-major",
+	]).unwrap();
+
+	assert_eq!(parsed.targets().len(), 1);
+	assert_eq!(parsed.targets()[0].format, slang::CompileTarget::Spirv);
+
+	// Depending on the Slang version, search paths and macros end up in the description or in its options
+	let options = parsed.options();
+	let has_search_path = parsed.search_paths().any(|path| path == "shaders")
+		|| options.to_command_line().windows(2).any(|args| args == ["-I", "shaders"]);
+	let has_macro = parsed.preprocessor_macros().any(|m| m == ("QUALITY", "2"))
+		|| options.to_command_line().iter().any(|arg| arg == "-DQUALITY=2");
+	assert!(has_search_path && has_macro);
+
+	// The parsed description is usable as is
+	let session = global_session.create_session(&parsed.session_desc()).unwrap();
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session.create_composite_component_type(&[module.into(), entry_point.into()]).unwrap();
+	assert!(!program.link().unwrap().entry_point_code(0, 0).unwrap().as_slice().is_empty());
+
+	assert!(global_session.parse_command_line(&["-no-such-option"]).is_err());
+}
+
+#[test]
+fn compiler_options_to_command_line()
+{
+	let options = slang::CompilerOptions::default()
+		.macro_define("QUALITY", "2")
+		.include("shaders")
+		.optimization(slang::OptimizationLevel::High)
+		.matrix_layout_row(true)
+		.matrix_layout_column(false)
+		.target(slang::CompileTarget::Spirv)
+		.vulkan_bind_shift(slang::VulkanShiftKind::ShaderResource, 1, 100)
+		.downstream_args("dxc", "-Zi\n-Qembed_debug");
+
+	assert_eq!(options.to_command_line(), [
+		"-DQUALITY=2", "-I", "shaders", "-O2", "-matrix-layout-row-major", "-target", "spirv",
+		"-fvk-t-shift", "100", "1", "-Xdxc", "-Zi", "-Xdxc", "-Qembed_debug",
+	]);
+}
+
+#[test]
+fn session_config()
+{
+	let config = slang::SessionConfig::new()
+		.target(slang::TargetConfig::new(slang::CompileTarget::Spirv).profile("glsl_450"))
+		.target(slang::TargetConfig::new(slang::CompileTarget::Wgsl))
+		.search_path("shaders")
+		.macro_define("SCALE", "3.0");
+
+	// The configuration owns everything, so it outlives the scope it was built in and can be shared
+	let config = std::thread::spawn(move || config).join().unwrap();
+	assert_eq!(config.clone(), config);
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = config.create_session(&global_session).unwrap();
+	let module = session.load_module_from_source_string(
+		"scaled",
+		"scaled.slang",
+		"RWStructuredBuffer<float> output;\n[shader(\"compute\")][numthreads(1,1,1)]\nvoid main() { output[0] *= SCALE; }",
+	).unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session.create_composite_component_type(&[module.into(), entry_point.into()]).unwrap();
+	let linked = program.link().unwrap();
+	assert!(linked.entry_point_code(0, 1).unwrap().as_str().unwrap().contains("3.0"));
+
+	let invalid = slang::SessionConfig::new().macro_define("A\0", "1");
+	assert!(invalid.create_session(&global_session).is_err());
+	let invalid = slang::SessionConfig::new().search_path("a\0b");
+	assert!(invalid.create_session(&global_session).is_err());
+}
+
+#[test]
+fn macro_set()
+{
+	let shared = slang::MacroSet::new().define("QUALITY", "1").define("DEBUG", "1");
+	let overrides = slang::MacroSet::new().define("QUALITY", "2");
+	let merged = shared.merged(&overrides).undefine("DEBUG");
+	assert_eq!(merged.iter().collect::<Vec<_>>(), [("QUALITY", "2")]);
+	assert_eq!(shared.get("QUALITY"), Some("1"));
+
+	// Definition order doesn't matter
+	let a = ["X", "Y"].into_iter().zip(["1", "2"]).collect::<slang::MacroSet>();
+	let b = slang::MacroSet::new().define("Y", "2").define("X", "1");
+	assert_eq!(a, b);
+	assert_eq!(a.to_string(), "X=1 Y=2");
+
+	assert!(slang::MacroSet::new().try_define("A\0", "1").is_err());
+	let invalid = slang::MacroSet::new().define("A\0", "1");
+	assert!(invalid.is_empty() && !invalid.is_valid());
+	assert!(!shared.merged(&invalid).is_valid());
+
+	let permutations = slang::MacroPermutations::new()
+		.axis("SHADOWS", [0, 1])
+		.axis("LIGHTS", [1, 4, 16]);
+	assert_eq!(permutations.len(), 6);
+	let all = permutations.iter().map(|macros| macros.to_string()).collect::<Vec<_>>();
+	assert_eq!(all, [
+		"LIGHTS=1 SHADOWS=0", "LIGHTS=4 SHADOWS=0", "LIGHTS=16 SHADOWS=0",
+		"LIGHTS=1 SHADOWS=1", "LIGHTS=4 SHADOWS=1", "LIGHTS=16 SHADOWS=1",
+	]);
+	assert!(permutations.get(6).is_none());
+}
+
+#[test]
+fn macro_permutations()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let config = slang::SessionConfig::new()
+		.target(slang::TargetConfig::new(slang::CompileTarget::Wgsl))
+		.search_path("shaders")
+		.macro_define("SCALE", "2.0");
+
+	let permutations = slang::MacroPermutations::new()
+		.axis("SHADOWS", [0, 1])
+		.axis("LIGHTS", [1, 4, 16]);
+	let compiled = permutations.compile(&global_session, &config, "permutations", "main").collect::<Vec<_>>();
+	assert_eq!(compiled.len(), 6);
+
+	let mut codes = std::collections::HashSet::new();
+	for (macros, result) in compiled {
+		let program = result.unwrap_or_else(|error| panic!("{macros}: {error}"));
+		assert_eq!(program.code.len(), 1);
+		codes.insert(program.code[0].clone());
+	}
+	assert_eq!(codes.len(), 6);
+
+	// A combination the shader rejects fails on its own
+	let invalid = slang::MacroPermutations::new().axis("LIGHTS", [1, 0]);
+	let results = invalid.compile(&global_session, &config, "permutations", "main")
+		.map(|(_, result)| result.is_ok())
+		.collect::<Vec<_>>();
+	assert_eq!(results, [true, false]);
+}
+
+#[test]
+fn permutation_key()
+{
+	let a = slang::PermutationKey::new().set("SHADOWS", 1).set("TMaterial", "Doubling");
+	let b = slang::PermutationKey::new().set("TMaterial", "Doubling").set("SHADOWS", 1);
+	assert_eq!(a, b);
+	assert_eq!(a.to_string(), "SHADOWS=1 TMaterial=Doubling");
+
+	// IDs are persisted by applications, so they must never change
+	assert_eq!(a.id(), b.id());
+	assert_eq!(a.id().to_string(), "3c2292d87b4223b3");
+	assert_ne!(a.id(), a.clone().set("SHADOWS", 0).id());
+}
+
+#[test]
+fn permutation_set()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let config = slang::SessionConfig::new()
+		.target(slang::TargetConfig::new(slang::CompileTarget::Wgsl))
+		.search_path("shaders");
+
+	let mut set = slang::PermutationSet::new(global_session, config, "permutation_set", "main")
+		.macro_axis("SHADOWS", [0, 1])
+		.type_axis("TMaterial", ["Doubling", "Halving"])
+		.constant_axis("LIGHT_COUNT", "int", [1, 4]);
+	assert_eq!(set.len(), 8);
+
+	let key = slang::PermutationKey::new().set("SHADOWS", 1).set("TMaterial", "Halving");
+	let variant = set.variant(&key).unwrap_or_else(|error| panic!("{error}"));
+	let id = variant.id;
+	assert_eq!(id, key.set("LIGHT_COUNT", 1).id());
+	assert_eq!(variant.timings.link.count, 1);
+	assert!(variant.reflection.parameters.iter().any(|parameter| parameter.name == "output"));
+	assert!(set.compiled(id).is_some());
+
+	// Unknown axes and values are rejected before compiling anything
+	assert!(set.variant(&slang::PermutationKey::new().set("QUALITY", 1)).is_err());
+	assert!(set.variant(&slang::PermutationKey::new().set("SHADOWS", 2)).is_err());
+
+	assert_eq!(set.compile_all().count(), 0);
+	let codes = set.keys()
+		.map(|key| set.compiled(key.id()).unwrap().code[0].clone())
+		.collect::<std::collections::HashSet<_>>();
+	assert_eq!(codes.len(), 8);
+
+	// A value that doesn't compile only fails its own variants
+	let config = slang::SessionConfig::new()
+		.target(slang::TargetConfig::new(slang::CompileTarget::Wgsl))
+		.search_path("shaders");
+	let mut set = slang::PermutationSet::new(slang::GlobalSession::new().unwrap(), config, "permutation_set", "main")
+		.type_axis("TMaterial", ["Doubling", "Missing"])
+		.constant_axis("LIGHT_COUNT", "int", [1]);
+	let failures = set.compile_all().map(|(key, _)| key.to_string()).collect::<Vec<_>>();
+	assert_eq!(failures, ["LIGHT_COUNT=1 TMaterial=Missing"]);
+
+	// Axes can't share a name, as their values would end up under the same key
+	let config = slang::SessionConfig::new().target(slang::TargetConfig::new(slang::CompileTarget::Wgsl));
+	let mut set = slang::PermutationSet::new(slang::GlobalSession::new().unwrap(), config, "permutation_set", "main")
+		.macro_axis("SHADOWS", [0, 1])
+		.constant_axis("SHADOWS", "int", [1]);
+	assert!(!set.is_valid());
+	assert_eq!(set.axes().len(), 1);
+	assert!(set.variant(&slang::PermutationKey::new()).is_err());
+
+	// Values are passed to Slang as C strings
+	let config = slang::SessionConfig::new()
+		.target(slang::TargetConfig::new(slang::CompileTarget::Wgsl))
+		.search_path("shaders");
+	let mut set = slang::PermutationSet::new(slang::GlobalSession::new().unwrap(), config, "permutation_set", "main")
+		.type_axis("TMaterial", ["Doubling\0"])
+		.constant_axis("LIGHT_COUNT", "int", [1]);
+	assert!(set.variant(&slang::PermutationKey::new()).is_err());
+}
+
+#[test]
+fn downstream_compilers()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+
+	let compilers = global_session.downstream_compilers();
+	assert_eq!(compilers.iter().len(), slang::PASS_THROUGHS.len());
+	assert_eq!(compilers.available().count() + compilers.unavailable().count(), slang::PASS_THROUGHS.len());
+	for (compiler, available) in compilers.iter() {
+		assert_eq!(global_session.check_pass_through_support(compiler), available);
+		assert_eq!(compilers.is_available(compiler), available);
+	}
+	assert_eq!(compilers.to_string().lines().count(), slang::PASS_THROUGHS.len());
+
+	let transition = (slang::CompileTarget::Hlsl, slang::CompileTarget::Dxil);
+	global_session.set_downstream_compiler_for_transition(transition.0, transition.1, slang::PassThrough::Fxc);
+	assert_eq!(global_session.downstream_compiler_for_transition(transition.0, transition.1), slang::PassThrough::Fxc);
+	global_session.set_downstream_compiler_for_transition(transition.0, transition.1, slang::PassThrough::None);
+	assert_eq!(global_session.downstream_compiler_for_transition(transition.0, transition.1), slang::PassThrough::None);
+
+	let default = global_session.default_downstream_compiler(slang::SourceLanguage::Cpp);
+	global_session.set_default_downstream_compiler(slang::SourceLanguage::Cpp, default).unwrap();
+	assert_eq!(global_session.default_downstream_compiler(slang::SourceLanguage::Cpp), default);
+
+	// Pointing at a compiler that doesn't exist makes it unavailable instead of failing
+	global_session.set_downstream_compiler_path(slang::PassThrough::Dxc, "missing/dxc").unwrap();
+	assert!(!global_session.check_pass_through_support(slang::PassThrough::Dxc));
+	assert!(global_session.set_downstream_compiler_path(slang::PassThrough::Dxc, "missing\0dxc").is_err());
+
+	assert!(global_session.set_spirv_core_grammar("missing.json").is_err());
+}
+
+#[test]
+fn language_prelude()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+
+	// Extend the default prelude rather than replacing it
+	let default = global_session.language_prelude(slang::SourceLanguage::Cpp);
+	let prelude = format!("{default}\n// custom prelude\n");
+
+	let config = slang::SessionConfig::new()
+		.target(slang::TargetConfig::new(slang::CompileTarget::CppSource))
+		.search_path("shaders")
+		.prelude(slang::SourceLanguage::Cpp, "replaced")
+		.prelude(slang::SourceLanguage::Cpp, &prelude);
+	assert_eq!(config.preludes.len(), 1);
+
+	config.with_session_desc(&global_session, |_| ()).unwrap();
+	assert_eq!(global_session.language_prelude(slang::SourceLanguage::Cpp), default);
+
+	let session = config.create_session(&global_session).unwrap();
+	assert_eq!(global_session.language_prelude(slang::SourceLanguage::Cpp), prelude);
+
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session.create_composite_component_type(&[module.into(), entry_point.into()]).unwrap();
+	let code = program.link().unwrap().entry_point_code(0, 0).unwrap();
+	assert!(code.as_str().unwrap().contains("// custom prelude"));
+
+	global_session.set_language_prelude(slang::SourceLanguage::Cpp, &default).unwrap();
+	assert_eq!(global_session.language_prelude(slang::SourceLanguage::Cpp), default);
+	assert!(global_session.set_language_prelude(slang::SourceLanguage::Cpp, "a\0b").is_err());
+}
+
+#[test]
+fn core_module()
+{
+	let dir = std::env::temp_dir().join(format!("slang-core-module-test-{}", std::process::id()));
+	let path = dir.join("core-module.bin");
+
+	// The first call compiles and saves the core module, the second one loads it
+	let compiled = slang::GlobalSession::with_cached_core_module(&path).unwrap();
+	let archive = std::fs::read(&path).unwrap();
+	assert!(!archive.is_empty());
+	let loaded = slang::GlobalSession::with_cached_core_module(&path).unwrap();
+	let embedded = slang::GlobalSession::with_core_module(&archive).unwrap();
+
+	for global_session in [compiled, loaded, embedded] {
+		let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+		let module = session.load_module("test.slang").unwrap();
+		let entry_point = module.find_entry_point_by_name("main").unwrap();
+		let program = session.create_composite_component_type(&[module.into(), entry_point.into()]).unwrap();
+		assert_ne!(program.link().unwrap().entry_point_code(0, 0).unwrap().as_slice().len(), 0);
+	}
+
+	// A damaged archive is replaced
+	std::fs::write(&path, b"damaged").unwrap();
+	assert!(slang::GlobalSession::with_core_module(b"damaged").is_err());
+	slang::GlobalSession::with_cached_core_module(&path).unwrap();
+	assert_ne!(std::fs::read(&path).unwrap(), b"damaged");
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn add_builtins()
+{
+	// Later sources can use earlier ones
+	let builtins = slang::BuiltinBundle::new()
+		.source("engine_scale.slang", "float engine_scale(float value) { return 2.0 * value; }")
+		.source(
+			"engine_scale_twice.slang",
+			"float engine_scale_twice(float value) { return engine_scale(engine_scale(value)); }",
+		);
+	let global_session = slang::GlobalSession::with_builtins(&builtins).unwrap();
+
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session.load_module_from_source_string(
+		"uses_builtins",
+		"uses_builtins.slang",
+		"RWStructuredBuffer<float> output;\n[shader(\"compute\")][numthreads(1,1,1)]\nvoid main() { output[0] = engine_scale_twice(output[0]); }",
+	).unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session.create_composite_component_type(&[module.into(), entry_point.into()]).unwrap();
+	assert_ne!(program.link().unwrap().entry_point_code(0, 0).unwrap().as_slice().len(), 0);
+
+	// Broken builtins are reported instead of added
+	let error = global_session.add_builtins("broken_builtin.slang", "float broken( {").unwrap_err();
+	assert!(error.diagnostics().unwrap().iter().any(|d| d.path.as_deref() == Some("broken_builtin.slang")));
+}
+
+#[test]
+fn compile_stats()
+{
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let before = global_session.elapsed_time();
+
+	let (code, timings) = slang::measure_operations(|| {
+		let module = session.load_module("test.slang").unwrap();
+		let entry_point = module.find_entry_point_by_name("main").unwrap();
+		let program = session.create_composite_component_type(&[module.into(), entry_point.into()]).unwrap();
+		let linked = program.link().unwrap();
+
+		// Nested measurements count towards the enclosing one
+		let (code, inner) = slang::measure_operations(|| linked.entry_point_code(0, 0).unwrap());
+		assert_eq!(inner.target_code.count, 1);
+		assert_eq!(inner.link.count, 0);
+		code
+	});
+	assert_ne!(code.as_slice().len(), 0);
+
+	assert_eq!(timings.load_module.count, 1);
+	assert_eq!(timings.link.count, 1);
+	assert_eq!(timings.target_code.count, 1);
+	assert!(timings.target_code.max <= timings.target_code.total);
+	assert!(timings.total() >= timings.target_code.total);
+
+	let stats = global_session.elapsed_time().since(&before);
+	assert!(stats.total > std::time::Duration::ZERO);
+	assert!(stats.downstream <= stats.total);
+	assert_eq!(stats.frontend(), stats.total - stats.downstream);
+
+	// Nothing is recorded outside of a measurement
+	let ((), timings) = slang::measure_operations(|| ());
+	assert_eq!(timings, slang::OperationTimings::default());
+}
+
+#[test]
+fn measure_operations_panic()
+{
+	let ((), timings) = slang::measure_operations(|| {
+		crate::stats::timed(crate::Operation::Link, || ());
+		let result = std::panic::catch_unwind(|| slang::measure_operations(|| {
+			crate::stats::timed(crate::Operation::LoadModule, || ());
+			panic!("failed to compile");
+		}));
+		assert!(result.is_err());
+	});
+
+	// The panicking measurement still ends and counts towards the enclosing one
+	assert_eq!(timings.link.count, 1);
+	assert_eq!(timings.load_module.count, 1);
+}